@@ -0,0 +1,34 @@
+// Camera-facing billboards for CPU particles, one instance per particle
+#import bevy_pbr::mesh_view_bindings::view
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    // Instance data: centre and size, then linear colour
+    @location(3) center_size: vec4<f32>,
+    @location(4) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let right = view.world_from_view[0].xyz;
+    let up = view.world_from_view[1].xyz;
+    let offset = (right * vertex.position.x + up * vertex.position.y) * vertex.center_size.w;
+    let world_position = vertex.center_size.xyz + offset;
+
+    var out: VertexOutput;
+    out.clip_position = view.clip_from_world * vec4<f32>(world_position, 1.0);
+    out.color = vertex.color;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
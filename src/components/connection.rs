@@ -1,13 +1,28 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+};
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component, MapEntities)]
 pub struct Connection {
     pub start: Entity,
     pub end: Entity,
     pub direction: Direction,
 }
 
-#[derive(Debug, Clone, Copy)]
+// Scenes hand out fresh entities; keep both ends pointing at the loaded nodes
+impl MapEntities for Connection {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.start = entity_mapper.map_entity(self.start);
+        self.end = entity_mapper.map_entity(self.end);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Reflect)]
 pub enum Direction {
     // Cardinal and Ordinal
     North,
@@ -2,7 +2,8 @@ use bevy::prelude::*;
 use crate::err::{Result, ComponentError};
 
 /// Component to mark an entity as a generated mesh
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct GeneratedMesh {
     pub variant: TridecahedronVariant,
     pub radius: f32,
@@ -10,7 +11,8 @@ pub struct GeneratedMesh {
 }
 
 /// Represents different variants of the tridecahedron mesh
-#[derive(Debug, Clone, Copy, Component)]
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+#[reflect(Component)]
 pub enum TridecahedronVariant {
     /// Standard tridecahedron with uniform scaling
     Alpha,
@@ -3,6 +3,7 @@ use crate::err::{Result, ComponentError};
 use crate::resources::uni_color::UniColor;
 
 #[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
 pub struct MagneticField {
     pub strength: f32,
     pub polarity: Polarity,
@@ -17,6 +18,15 @@ pub enum Polarity {
     South,
 }
 
+impl Polarity {
+    pub fn flipped(self) -> Self {
+        match self {
+            Polarity::North => Polarity::South,
+            Polarity::South => Polarity::North,
+        }
+    }
+}
+
 impl Default for MagneticField {
     fn default() -> Self {
         Self {
@@ -45,6 +55,29 @@ impl MagneticField {
         Ok(interaction_strength)
     }
 
+    /// Dipole moment: strength along the field orientation in the XZ plane,
+    /// pointing outward for north and inward for south
+    pub fn dipole_moment(&self) -> Vec3 {
+        let sign = match self.polarity {
+            Polarity::North => 1.0,
+            Polarity::South => -1.0,
+        };
+        Vec3::new(self.orientation.cos(), 0.0, self.orientation.sin()) * self.strength * sign
+    }
+
+    /// Magnetic flux density at `point` from this field's dipole located at `origin`.
+    /// The distance is softened by `interaction_radius` so the field stays finite at the node.
+    pub fn field_at(&self, origin: Vec3, point: Vec3) -> Vec3 {
+        let offset = point - origin;
+        let softening = self.interaction_radius * 0.25;
+        let distance_squared = offset.length_squared() + softening * softening;
+        let distance = distance_squared.sqrt();
+        let direction = offset / distance;
+        let moment = self.dipole_moment();
+
+        (3.0 * moment.dot(direction) * direction - moment) / (distance_squared * distance)
+    }
+
     pub fn get_color(&self) -> Color {
         match self.polarity {
             Polarity::North => UniColor::srgb(0.0, 0.5, 1.0).as_bevy_color(), // Blue for North
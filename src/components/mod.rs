@@ -2,19 +2,24 @@ use bevy::prelude::*;
 use bevy::render::mesh::Mesh;
 use crate::resources::uni_color::UniColor;
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct CameraController;
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct MainCamera;
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Mesh3d(pub Handle<Mesh>);
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct MeshMaterial3d(pub Handle<StandardMaterial>);
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct WindowBorder {
     pub color: UniColor,
 }
@@ -32,11 +37,18 @@ pub mod connection;
 pub mod generated_mesh;
 pub mod magnetic_field;
 pub mod particle_emitter;
+pub mod selection;
 pub mod shapes;
+pub mod trail;
 
 pub use node::{Node, ShapeType};
 pub use connection::{Connection, Direction};
 pub use generated_mesh::{GeneratedMesh, TridecahedronVariant};
 pub use magnetic_field::{MagneticField, Polarity};
-pub use particle_emitter::{ParticleEmitter, EmitterShape, InteractionEffect};
+pub use particle_emitter::{
+    ChargedParticle, CpuParticleSettings, EmitterShape, EmitterVelocity, InteractionEffect, Particle,
+    ParticleColorOverLife, ParticleEmitter,
+};
+pub use selection::{Dragged, Hovered, Selected};
 pub use shapes::*;
+pub use trail::{MotionTrail, TrailSample};
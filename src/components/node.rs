@@ -3,6 +3,7 @@ use bevy::prelude::*;
 use crate::components::magnetic_field::MagneticField;
 
 #[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
 pub struct Node {
     pub shape_type: ShapeType,
     pub rotation: f32,
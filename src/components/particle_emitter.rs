@@ -1,22 +1,213 @@
-use bevy::prelude::*;
+use std::sync::Arc;
+use bevy::{
+    prelude::*,
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    render::mesh::{Mesh, VertexAttributeValues},
+};
+use rand::Rng;
 use crate::err::{Result, Error};
 use crate::resources::uni_color::UniColor;
 
 /// Configuration for different types of particle emitters
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Reflect)]
 pub enum EmitterShape {
     Sphere { radius: f32 },
     Cone { height: f32, radius: f32 },
     Box { size: Vec3 },
+    /// Surface of a torus around the Y axis
+    Torus { major_radius: f32, minor_radius: f32 },
+    /// Uniformly along the segment between two local points
+    Line { start: Vec3, end: Vec3 },
+    /// Surface of the ellipsoid with these semi-axes, at uniformly random directions
+    Ellipsoid { half_extents: Vec3 },
+    /// Points pre-sampled from a mesh surface with their face normals.
+    /// GPU effects spawn from `gpu_approximation` instead.
+    MeshSurface {
+        /// Not reflected; a copy built through reflection has no points and fails `validate`
+        #[reflect(ignore)]
+        points: Arc<[(Vec3, Vec3)]>,
+        half_extents: Vec3,
+    },
+}
+
+/// How particles leave an emitter
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub enum EmitterVelocity {
+    /// Straight out from the emitter origin
+    #[default]
+    Radial,
+    /// Circling `axis` through the emitter origin
+    Tangential { axis: Vec3 },
+    /// Following the dipole field lines of a moment along `axis`
+    AlongField { axis: Vec3 },
+}
+
+impl EmitterVelocity {
+    /// Unit emission direction for a particle spawned at local `position`.
+    /// `fallback` is used where the mode is undefined, such as at the origin.
+    pub fn direction(&self, position: Vec3, fallback: Vec3) -> Vec3 {
+        match self {
+            EmitterVelocity::Radial => position.normalize_or(fallback),
+            EmitterVelocity::Tangential { axis } => axis.cross(position).normalize_or(fallback),
+            EmitterVelocity::AlongField { axis } => {
+                let Some(r) = position.try_normalize() else {
+                    return axis.normalize_or(fallback);
+                };
+                let moment = axis.normalize_or_zero();
+                (3.0 * moment.dot(r) * r - moment).normalize_or(fallback)
+            }
+        }
+    }
+}
+
+impl EmitterShape {
+    /// The shape GPU effects spawn from in place of this one, if they cannot
+    /// reproduce it. Hanabi has no way to index the pre-sampled points of a
+    /// `MeshSurface`, so it spawns from the ellipsoid through their extents.
+    pub fn gpu_approximation(&self) -> Option<EmitterShape> {
+        match self {
+            EmitterShape::MeshSurface { half_extents, .. } => Some(EmitterShape::Ellipsoid {
+                half_extents: *half_extents,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Sample a local spawn position and an emission direction for this shape.
+    ///
+    /// Cones have their base disc of `radius` at the origin and their apex at
+    /// `height` on +Y; particles leave them heading away from the axis and up.
+    pub fn sample(&self, rng: &mut impl Rng) -> (Vec3, Vec3) {
+        match self {
+            EmitterShape::Sphere { radius } => {
+                let direction = random_unit_vector(rng);
+                let distance = radius * rng.gen::<f32>().cbrt();
+                (direction * distance, direction)
+            }
+            EmitterShape::Cone { height, radius } => {
+                // Volume density falls off quadratically towards the apex
+                let t = 1.0 - rng.gen::<f32>().cbrt();
+                let ring = radius * (1.0 - t) * rng.gen::<f32>().sqrt();
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let position = Vec3::new(ring * angle.cos(), height * t, ring * angle.sin());
+                let direction = Vec3::new(position.x, *height, position.z).normalize_or(Vec3::Y);
+                (position, direction)
+            }
+            EmitterShape::Box { size } => {
+                let half = *size * 0.5;
+                let position = Vec3::new(
+                    rng.gen_range(-half.x..=half.x),
+                    rng.gen_range(-half.y..=half.y),
+                    rng.gen_range(-half.z..=half.z),
+                );
+                (position, random_unit_vector(rng))
+            }
+            EmitterShape::Torus { major_radius, minor_radius } => {
+                let around = rng.gen_range(0.0..std::f32::consts::TAU);
+                let tube = rng.gen_range(0.0..std::f32::consts::TAU);
+                let ring = Vec3::new(around.cos(), 0.0, around.sin());
+                let normal = ring * tube.cos() + Vec3::Y * tube.sin();
+                (ring * *major_radius + normal * *minor_radius, normal)
+            }
+            EmitterShape::Line { start, end } => {
+                let position = start.lerp(*end, rng.gen::<f32>());
+                (position, random_unit_vector(rng))
+            }
+            EmitterShape::Ellipsoid { half_extents } => {
+                let position = random_unit_vector(rng) * *half_extents;
+                let normal = (position / (*half_extents * *half_extents)).normalize_or(Vec3::Y);
+                (position, normal)
+            }
+            EmitterShape::MeshSurface { points, .. } => {
+                if points.is_empty() {
+                    return (Vec3::ZERO, random_unit_vector(rng));
+                }
+                points[rng.gen_range(0..points.len())]
+            }
+        }
+    }
+
+    /// Sample `count` points uniformly by area over a triangle mesh's surface
+    pub fn mesh_surface(mesh: &Mesh, count: usize, rng: &mut impl Rng) -> Result<Self> {
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            return Err(Error::custom("Mesh emitter needs Float32x3 vertex positions"));
+        };
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(indices) => indices.iter().collect(),
+            None => (0..positions.len()).collect(),
+        };
+
+        let mut triangles = Vec::with_capacity(indices.len() / 3);
+        let mut total_area = 0.0;
+        for face in indices.chunks_exact(3) {
+            let [a, b, c] = [face[0], face[1], face[2]].map(|i| positions.get(i).copied().map(Vec3::from));
+            let (Some(a), Some(b), Some(c)) = (a, b, c) else {
+                return Err(Error::custom("Mesh emitter index out of range"));
+            };
+            let cross = (b - a).cross(c - a);
+            total_area += cross.length() * 0.5;
+            triangles.push((a, b, c, cross.normalize_or(Vec3::Y), total_area));
+        }
+        if total_area <= 0.0 {
+            return Err(Error::custom("Mesh emitter needs a surface with non-zero area"));
+        }
+
+        let points: Vec<(Vec3, Vec3)> = (0..count)
+            .map(|_| {
+                let target = rng.gen::<f32>() * total_area;
+                let index = triangles
+                    .partition_point(|triangle| triangle.4 < target)
+                    .min(triangles.len() - 1);
+                let (a, b, c, normal, _) = triangles[index];
+                // Uniform barycentric sample
+                let (mut u, mut v) = (rng.gen::<f32>(), rng.gen::<f32>());
+                if u + v > 1.0 {
+                    u = 1.0 - u;
+                    v = 1.0 - v;
+                }
+                (a + (b - a) * u + (c - a) * v, normal)
+            })
+            .collect();
+
+        let half_extents = points
+            .iter()
+            .fold(Vec3::ZERO, |extents, (point, _)| extents.max(point.abs()));
+
+        Ok(EmitterShape::MeshSurface {
+            points: points.into(),
+            half_extents,
+        })
+    }
+}
+
+fn random_unit_vector(rng: &mut impl Rng) -> Vec3 {
+    loop {
+        let v = Vec3::new(
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+        );
+        let length_squared = v.length_squared();
+        if length_squared > 1e-4 && length_squared <= 1.0 {
+            return v / length_squared.sqrt();
+        }
+    }
 }
 
 /// Component for configurable particle emitters
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
 pub struct ParticleEmitter {
     pub active: bool,
     pub lifetime: f32,
     pub elapsed: f32,
     pub shape: EmitterShape,
+    pub velocity: EmitterVelocity,
+    /// Initial particle speed for GPU effects built from this emitter
+    pub speed: f32,
     pub polarity: bool, // true for north, false for south
 }
 
@@ -27,6 +218,8 @@ impl Default for ParticleEmitter {
             lifetime: 5.0,
             elapsed: 0.0,
             shape: EmitterShape::Sphere { radius: 1.0 },
+            velocity: EmitterVelocity::Radial,
+            speed: 1.0,
             polarity: true,
         }
     }
@@ -39,10 +232,18 @@ impl ParticleEmitter {
             lifetime,
             elapsed: 0.0,
             shape,
+            velocity: EmitterVelocity::Radial,
+            speed: 1.0,
             polarity,
         }
     }
 
+    pub fn with_velocity(mut self, velocity: EmitterVelocity, speed: f32) -> Self {
+        self.velocity = velocity;
+        self.speed = speed;
+        self
+    }
+
     pub fn is_active(&self) -> bool {
         self.active
     }
@@ -68,7 +269,33 @@ impl ParticleEmitter {
         if self.lifetime <= 0.0 {
             return Err(Error::custom("Particle emitter lifetime must be positive"));
         }
-        Ok(())
+        if !self.speed.is_finite() || self.speed < 0.0 {
+            return Err(Error::custom("Particle emitter speed must be finite and non-negative"));
+        }
+
+        let valid_shape = match &self.shape {
+            EmitterShape::Sphere { radius } => *radius > 0.0,
+            EmitterShape::Cone { height, radius } => *height > 0.0 && *radius > 0.0,
+            EmitterShape::Box { size } => size.cmpgt(Vec3::ZERO).all(),
+            EmitterShape::Torus { major_radius, minor_radius } => {
+                *minor_radius > 0.0 && *major_radius >= *minor_radius
+            }
+            EmitterShape::Line { start, end } => start.is_finite() && end.is_finite(),
+            EmitterShape::Ellipsoid { half_extents } => half_extents.cmpgt(Vec3::ZERO).all(),
+            EmitterShape::MeshSurface { points, .. } => !points.is_empty(),
+        };
+        if !valid_shape {
+            return Err(Error::custom("Particle emitter shape has invalid dimensions"));
+        }
+
+        match self.velocity {
+            EmitterVelocity::Tangential { axis } | EmitterVelocity::AlongField { axis }
+                if axis.length_squared() < 1e-6 =>
+            {
+                Err(Error::custom("Particle emitter velocity axis must be non-zero"))
+            }
+            _ => Ok(()),
+        }
     }
 
     pub fn get_polarity(&self) -> bool {
@@ -77,15 +304,112 @@ impl ParticleEmitter {
 }
 
 /// Component to track particle effects associated with specific interactions
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component, MapEntities)]
 pub struct InteractionEffect {
     pub source: Entity,
     pub target: Entity,
     pub strength: f32,
 }
 
+impl MapEntities for InteractionEffect {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.source = entity_mapper.map_entity(self.source);
+        self.target = entity_mapper.map_entity(self.target);
+    }
+}
+
+/// Settings for emitters simulated by the CPU particle path instead of hanabi
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct CpuParticleSettings {
+    pub spawn_rate: f32,
+    pub max_particles: usize,
+    pub particle_lifetime: f32,
+    pub speed: f32,
+    pub size: f32,
+    pub acceleration: Vec3,
+    pub start_color: UniColor,
+    pub end_color: UniColor,
+    /// Charge given to spawned particles; zero spawns neutral particles
+    pub charge: f32,
+    /// Fractional particles carried over between frames
+    pub spawn_accumulator: f32,
+}
+
+impl Default for CpuParticleSettings {
+    fn default() -> Self {
+        Self {
+            spawn_rate: 50.0,
+            max_particles: 512,
+            particle_lifetime: 1.5,
+            speed: 1.0,
+            size: 0.05,
+            acceleration: Vec3::ZERO,
+            start_color: UniColor::srgb(0.5, 0.8, 1.0),
+            end_color: UniColor::srgb(0.3, 0.4, 0.8),
+            charge: 0.0,
+            spawn_accumulator: 0.0,
+        }
+    }
+}
+
+impl CpuParticleSettings {
+    /// Settings coloured for the emitter's polarity (blue north, red south)
+    pub fn for_polarity(is_north: bool) -> Self {
+        let (start_color, end_color) = if is_north {
+            (UniColor::srgb(0.0, 0.5, 1.0), UniColor::srgb(0.4, 0.7, 1.0))
+        } else {
+            (UniColor::srgb(1.0, 0.2, 0.0), UniColor::srgb(1.0, 0.6, 0.4))
+        };
+        Self {
+            start_color,
+            end_color,
+            charge: if is_north { 1.0 } else { -1.0 },
+            ..default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.spawn_rate < 0.0 {
+            return Err(Error::custom("CPU particle spawn rate cannot be negative"));
+        }
+        if self.particle_lifetime <= 0.0 {
+            return Err(Error::custom("CPU particle lifetime must be positive"));
+        }
+        if self.size <= 0.0 {
+            return Err(Error::custom("CPU particle size must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Colour-over-life endpoints for a CPU particle, and the emitter that spawned it
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component, MapEntities)]
+pub struct ParticleColorOverLife {
+    pub start: UniColor,
+    pub end: UniColor,
+    pub emitter: Entity,
+}
+
+impl MapEntities for ParticleColorOverLife {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.emitter = entity_mapper.map_entity(self.emitter);
+    }
+}
+
+/// Gives a CPU particle an electric charge so it is steered by nearby
+/// `MagneticField`s via the Lorentz force
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct ChargedParticle {
+    pub charge: f32,
+}
+
 /// A single particle in the particle system
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Particle {
     pub lifetime: f32,
     pub max_lifetime: f32,
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+/// Marks a node currently under the pointer
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct Hovered;
+
+/// Marks a node that is part of the current selection
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct Selected;
+
+/// A node being dragged in a camera-aligned plane. Physics keeps acting on
+/// the other nodes but does not move this one.
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct Dragged {
+    /// Offset from the grabbed point to the node origin
+    pub offset: Vec3,
+}
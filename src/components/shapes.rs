@@ -8,7 +8,8 @@ use bevy::{
 };
 use crate::components::node::ShapeType;
 
-#[derive(Debug, Clone, Component)]
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
 pub enum Shape {
     Box(Box3d),
     Sphere(Sphere3d),
@@ -18,21 +19,21 @@ pub enum Shape {
     Torus(Torus3d),
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Reflect)]
 pub struct Box3d {
     pub width: f32,
     pub height: f32,
     pub depth: f32,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Reflect)]
 pub struct Sphere3d {
     pub radius: f32,
     pub sectors: usize,
     pub stacks: usize,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Reflect)]
 pub struct Capsule3d {
     pub radius: f32,
     pub height: f32,
@@ -41,7 +42,7 @@ pub struct Capsule3d {
     pub longitudes: usize,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Reflect)]
 pub struct Cylinder3d {
     pub radius: f32,
     pub height: f32,
@@ -49,14 +50,14 @@ pub struct Cylinder3d {
     pub segments: usize,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Reflect)]
 pub struct Cone3d {
     pub radius: f32,
     pub height: f32,
     pub resolution: usize,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Reflect)]
 pub struct Torus3d {
     pub radius: f32,
     pub ring_radius: f32,
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+/// One recorded position of a moving node
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct TrailSample {
+    pub position: Vec3,
+    /// Speed between the previous sample and this one
+    pub speed: f32,
+}
+
+/// Fixed-capacity ring buffer of past node positions. Once full, each new
+/// sample overwrites the oldest, so a trail never allocates after creation.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct MotionTrail {
+    samples: Vec<TrailSample>,
+    head: usize,
+    capacity: usize,
+    /// Seconds since the last sample was recorded
+    pub since_sample: f32,
+}
+
+impl MotionTrail {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: Vec::with_capacity(capacity),
+            head: 0,
+            capacity: capacity.max(1),
+            since_sample: 0.0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.head = 0;
+        self.since_sample = 0.0;
+    }
+
+    /// Record `position`, deriving speed from the newest sample and the
+    /// `elapsed` seconds since it was taken
+    pub fn push(&mut self, position: Vec3, elapsed: f32) {
+        let speed = match self.latest() {
+            Some(last) if elapsed > 0.0 => last.position.distance(position) / elapsed,
+            _ => 0.0,
+        };
+        let sample = TrailSample { position, speed };
+
+        if self.samples.len() < self.capacity {
+            self.samples.push(sample);
+        } else {
+            self.samples[self.head] = sample;
+            self.head = (self.head + 1) % self.capacity;
+        }
+    }
+
+    pub fn latest(&self) -> Option<&TrailSample> {
+        if self.samples.len() < self.capacity {
+            self.samples.last()
+        } else {
+            self.samples.get((self.head + self.capacity - 1) % self.capacity)
+        }
+    }
+
+    /// Samples from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &TrailSample> {
+        let (newer, older) = self.samples.split_at(self.head);
+        older.iter().chain(newer.iter())
+    }
+
+    /// Change the capacity, keeping the newest samples that still fit
+    pub fn resize(&mut self, capacity: usize) {
+        let capacity = capacity.max(1);
+        if capacity == self.capacity {
+            return;
+        }
+        let kept: Vec<TrailSample> = self.iter().copied().collect();
+        let skip = kept.len().saturating_sub(capacity);
+
+        self.samples = kept.into_iter().skip(skip).collect();
+        self.samples.reserve(capacity - self.samples.len());
+        self.head = 0;
+        self.capacity = capacity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trail_ring_buffer_keeps_newest() {
+        let mut trail = MotionTrail::new(3);
+        for x in 0..5 {
+            trail.push(Vec3::new(x as f32, 0.0, 0.0), 0.5);
+        }
+
+        let xs: Vec<f32> = trail.iter().map(|sample| sample.position.x).collect();
+        assert_eq!(xs, vec![2.0, 3.0, 4.0]);
+        assert_eq!(trail.latest().unwrap().position.x, 4.0);
+        assert!((trail.latest().unwrap().speed - 2.0).abs() < 1e-6);
+
+        trail.resize(2);
+        let xs: Vec<f32> = trail.iter().map(|sample| sample.position.x).collect();
+        assert_eq!(xs, vec![3.0, 4.0]);
+
+        trail.resize(4);
+        trail.push(Vec3::new(5.0, 0.0, 0.0), 0.5);
+        assert_eq!(trail.len(), 3);
+        assert_eq!(trail.iter().next().unwrap().position.x, 3.0);
+    }
+}
@@ -1,5 +1,5 @@
-use bevy::prelude::*;
-use std::sync::Arc;
+use bevy::{prelude::*, utils::Instant};
+use std::{collections::VecDeque, sync::Arc, time::Duration};
 use parking_lot::RwLock;
 use thiserror::Error;
 
@@ -88,16 +88,23 @@ impl Error {
     }
 }
 
+/// How long a reported error counts as recent
+pub const RECENT_ERROR_WINDOW: Duration = Duration::from_secs(30);
+
 /// Centralized error manager that integrates with Bevy's systems
 #[derive(Resource)]
 pub struct ErrorManager {
     errors: Arc<RwLock<Vec<Error>>>,
+    /// When each error was reported; outlives `clear` so the HUD can count
+    /// errors that were already drained
+    reported: Arc<RwLock<VecDeque<Instant>>>,
 }
 
 impl Default for ErrorManager {
     fn default() -> Self {
         Self {
             errors: Arc::new(RwLock::new(Vec::new())),
+            reported: Arc::new(RwLock::new(VecDeque::new())),
         }
     }
 }
@@ -107,6 +114,22 @@ impl ErrorManager {
     pub fn report_error(&self, error: Error) {
         error!("Error occurred: {}", error);
         self.errors.write().push(error);
+        let mut reported = self.reported.write();
+        reported.push_back(Instant::now());
+        Self::prune(&mut reported);
+    }
+
+    /// Errors reported within the last `RECENT_ERROR_WINDOW`
+    pub fn recent_error_count(&self) -> usize {
+        let mut reported = self.reported.write();
+        Self::prune(&mut reported);
+        reported.len()
+    }
+
+    fn prune(reported: &mut VecDeque<Instant>) {
+        while reported.front().is_some_and(|time| time.elapsed() > RECENT_ERROR_WINDOW) {
+            reported.pop_front();
+        }
     }
 
     /// Handle an error with recovery message
@@ -148,8 +171,9 @@ impl<T> ErrorExt<T> for Result<T, Error> {
     }
 }
 
-/// Bevy system that periodically checks and handles accumulated errors
-pub fn error_check_system(error_manager: Res<ErrorManager>, time: Res<Time>) {
+/// Bevy system that periodically checks and handles accumulated errors. Runs
+/// on real time so errors are still drained while the simulation is paused.
+pub fn error_check_system(error_manager: Res<ErrorManager>, time: Res<Time<Real>>) {
     const ERROR_CHECK_INTERVAL: f32 = 1.0; // Check every second
 
     let current_time = time.elapsed_secs_f64() as f32;
@@ -164,3 +188,19 @@ pub fn error_check_system(error_manager: Res<ErrorManager>, time: Res<Time>) {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_errors_survive_clear() {
+        let manager = ErrorManager::default();
+        manager.report_error(Error::custom("first"));
+        manager.report_error(ResourceError::NotFound("second".to_string()).into());
+        assert!(manager.has_errors());
+
+        manager.clear();
+        assert!(!manager.has_errors());
+        assert_eq!(manager.recent_error_count(), 2);
+    }
+}
@@ -47,51 +47,93 @@ pub mod systems;
 use bevy::{
     prelude::*,
     app::PluginGroup,
+    diagnostic::{Diagnostic, FrameTimeDiagnosticsPlugin, RegisterDiagnostic},
     log::LogPlugin,
+    picking::mesh_picking::MeshPickingPlugin,
+    time::TimeSystem,
 };
 use bevy_hanabi::HanabiPlugin;
+use bevy_inspector_egui::{bevy_egui::EguiPlugin, DefaultInspectorConfigPlugin};
 use bevy_tweening::TweeningPlugin;
 use bevy_mod_outline::OutlinePlugin;
+use leafwing_input_manager::{
+    plugin::InputManagerSystem,
+    prelude::{ActionState, InputManagerPlugin, InputMap},
+};
 
 use crate::{
-    resources::{HelixConfig, MaterialHandles},
+    resources::{
+        CameraBookmarks, CameraPath, CameraPathLoader, CameraPathPlayer, CameraState, FieldVisualization, HelixConfig, RegenerateHelix, RestoreSnapshot, SimulationHistory, LorentzConfig, TrailSettings, MaterialColors, MaterialHandles, NodeSpawnSettings, ParticleBackend, ParticleBudget,
+        Selection, SelectionChanged, ViewportLayout,
+        Theme, ThemeLoader, ThemeSettings,
+        EffectDefinition, EffectDefinitionLoader, EffectLibrary, EffectRegistry,
+        HyvoAction, InputBindings, InputBindingsHandle, InputBindingsLoader,
+        SimulationConfig,
+    },
     systems::{
+        budget::update_particle_budget,
+        camera::{
+            apply_orbit_camera, camera_bookmarks, control_camera_path, frame_nodes,
+            load_camera_paths, orbit_camera_input, play_camera_path,
+        },
         setup::{setup_materials, setup_camera, setup_scene},
         intersections::check_intersections,
-        magnetic::update_magnetic_fields,
-        node_visuals::update_node_visuals,
-        particles::update_particles,
+        magnetic::{update_interaction_effects, update_magnetic_fields, InteractionPairs, PHYSICS_TICK_TIME},
+        node_visuals::{
+            attach_node_effects, pulse_selected_nodes, setup_node_effects, update_node_effects, update_node_visuals,
+        },
+        particle_instancing::ParticleInstancingPlugin,
+        particles::{setup_particle_system, spawn_requested_emitters, update_particles, SpawnEmitter},
         generation::generate_helix,
+        hud::{setup_hud, toggle_hud, update_hud},
+        history::{record_history, restore_snapshot, scrub_timeline, setup_timeline, toggle_timeline, update_timeline},
+        simulation::{control_simulation, request_initial_helix, sync_simulation_clock},
+        inspector::{inspector_ui, release_input_captured_by_inspector, toggle_inspector, InspectorState},
+        input::{apply_input_bindings, exit_on_action, load_input_bindings, report_input_binding_errors, take_screenshot},
+        theme::{apply_theme, cycle_theme, load_themes, report_theme_errors},
+        field_visualization::{
+            control_field_visualization, draw_field_glyphs, drag_field_slice, setup_field_slice, update_field_slice,
+        },
+        trails::{attach_motion_trails, draw_motion_trails, record_motion_trails, toggle_motion_trails},
+        viewports::{setup_ortho_views, toggle_viewport_split, update_viewports, zoom_ortho_views},
+        effect_definitions::{apply_effect_definitions, load_effect_definitions, report_effect_definition_errors},
+        outline::{track_node_hover, update_node_outlines},
+        selection::{setup_selection_box, sync_selection, update_selection},
+        cpu_particles::{
+            assign_cpu_backend, integrate_cpu_particles, render_cpu_particles,
+            setup_cpu_particle_renderer, spawn_cpu_particles, steer_charged_particles, CpuParticleStats,
+        },
+        manipulation::{
+            cycle_spawn_settings, delete_selected_nodes, drag_nodes, spawn_node_at_cursor,
+            toggle_node_polarity,
+        },
     },
     err::{ErrorManager, error_check_system},
 };
 
 // Re-export error types from err module
 pub use crate::err::{Error, ComponentError, ResourceError, SystemError};
-
-#[derive(Resource)]
-pub struct AnimationState {
-    pub paused: bool,
-    pub speed: f32,
-}
-
-impl Default for AnimationState {
-    fn default() -> Self {
-        Self {
-            paused: false,
-            speed: 1.0,
-        }
-    }
-}
+pub use crate::resources::AnimationState;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum HyvoGridSet {
+    Input,
     Setup,
     Physics,
     Rendering,
     ErrorHandling,
 }
 
+/// Finer ordering inside a `HyvoGridSet`, for systems that read what another
+/// system in the same set wrote this frame
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum HyvoGridStep {
+    /// Acts on this frame's hover state
+    Selection,
+    /// Bends particle velocities before this frame's integration
+    Steering,
+}
+
 pub struct HyvoGrid;
 
 impl Default for HyvoGrid {
@@ -113,39 +155,246 @@ impl Plugin for HyvoGrid {
         app.add_plugins(HanabiPlugin);
         app.add_plugins(TweeningPlugin);
         app.add_plugins(OutlinePlugin);
+        app.add_plugins(MeshPickingPlugin);
+        app.add_plugins(FrameTimeDiagnosticsPlugin);
+        app.add_plugins(EguiPlugin);
+        app.add_plugins(DefaultInspectorConfigPlugin);
+        app.add_plugins(InputManagerPlugin::<HyvoAction>::default());
+        app.add_plugins(ParticleInstancingPlugin);
 
         // SAFETY: Resources must be initialized separately to maintain clear dependency chains
         app.init_resource::<AnimationState>();
         app.init_resource::<MaterialHandles>();
         app.init_resource::<HelixConfig>();
         app.init_resource::<ErrorManager>();
+        app.init_resource::<MaterialColors>();
+        app.init_resource::<ThemeSettings>();
+        app.init_resource::<Selection>();
+        app.init_resource::<NodeSpawnSettings>();
+        match ParticleBackend::from_env() {
+            Ok(backend) => app.insert_resource(backend),
+            Err(e) => {
+                app.world()
+                    .resource::<ErrorManager>()
+                    .report_with_recovery(e, "Using the default particle backend");
+                app.init_resource::<ParticleBackend>()
+            }
+        };
+        app.init_resource::<CpuParticleStats>();
+        app.init_resource::<LorentzConfig>();
+        app.init_resource::<InteractionPairs>();
+        app.init_resource::<ParticleBudget>();
+        app.init_resource::<EffectLibrary>();
+        app.init_resource::<EffectRegistry>();
+        app.init_resource::<TrailSettings>();
+        app.init_resource::<FieldVisualization>();
+        app.init_resource::<CameraState>();
+        app.init_resource::<CameraBookmarks>();
+        app.init_resource::<CameraPathPlayer>();
+        app.init_resource::<ViewportLayout>();
+        app.init_resource::<SimulationHistory>();
+        app.init_resource::<SimulationConfig>();
+        app.init_resource::<InspectorState>();
+        app.init_resource::<ActionState<HyvoAction>>();
+        app.init_resource::<InputBindingsHandle>();
+        // Defaults apply until the user's overrides finish loading
+        app.insert_resource::<InputMap<HyvoAction>>(InputBindings::default().input_map());
+
+        // SAFETY: Every component and resource registered before scenes or the inspector reflect over them; field types follow
+        app.register_type::<components::CameraController>();
+        app.register_type::<components::MainCamera>();
+        app.register_type::<components::Mesh3d>();
+        app.register_type::<components::MeshMaterial3d>();
+        app.register_type::<components::WindowBorder>();
+        app.register_type::<components::Node>();
+        app.register_type::<components::MagneticField>();
+        app.register_type::<components::Connection>();
+        app.register_type::<components::GeneratedMesh>();
+        app.register_type::<components::TridecahedronVariant>();
+        app.register_type::<components::Shape>();
+        app.register_type::<components::ParticleEmitter>();
+        app.register_type::<components::InteractionEffect>();
+        app.register_type::<components::CpuParticleSettings>();
+        app.register_type::<components::ParticleColorOverLife>();
+        app.register_type::<components::ChargedParticle>();
+        app.register_type::<components::Particle>();
+        app.register_type::<components::Hovered>();
+        app.register_type::<components::Selected>();
+        app.register_type::<components::Dragged>();
+        app.register_type::<components::MotionTrail>();
+        app.register_type::<resources::AnimationState>();
+        app.register_type::<resources::CameraState>();
+        app.register_type::<resources::NodeSpawnSettings>();
+        app.register_type::<resources::ParticleBackend>();
+        app.register_type::<resources::LorentzConfig>();
+        app.register_type::<resources::TrailSettings>();
+        app.register_type::<resources::HyvoGridConfig>();
+        app.register_type::<resources::SimulationConfig>();
+        app.register_type::<resources::HelixConfig>();
+        app.register_type::<resources::MaterialColors>();
+        app.register_type::<resources::MaterialHandles>();
+        app.register_type::<resources::ThemeSettings>();
+        app.register_type::<resources::Selection>();
+        app.register_type::<resources::ParticleBudget>();
+        app.register_type::<resources::BudgetCulled>();
+        app.register_type::<resources::EffectRegistry>();
+        app.register_type::<resources::EffectLibrary>();
+        app.register_type::<resources::FieldVisualization>();
+        app.register_type::<resources::CameraBookmarks>();
+        app.register_type::<resources::CameraPathPlayer>();
+        app.register_type::<resources::ViewportLayout>();
+        app.register_type::<resources::InputBindingsHandle>();
+        app.register_type::<resources::SimulationHistory>();
+
+        // SAFETY: Diagnostics registered before the systems that measure them
+        app.register_diagnostic(Diagnostic::new(PHYSICS_TICK_TIME).with_suffix("ms"));
+
+        // SAFETY: Events registered alongside resources so readers never miss a frame
+        app.add_event::<SelectionChanged>();
+        app.add_event::<RegenerateHelix>();
+        app.add_event::<RestoreSnapshot>();
+        app.add_event::<SpawnEmitter>();
+
+        // SAFETY: Asset types and loaders registered before any system can request them
+        app.init_asset::<Theme>();
+        app.init_asset_loader::<ThemeLoader>();
+        app.init_asset::<EffectDefinition>();
+        app.init_asset_loader::<EffectDefinitionLoader>();
+        app.init_asset::<CameraPath>();
+        app.init_asset_loader::<CameraPathLoader>();
+        app.init_asset::<InputBindings>();
+        app.init_asset_loader::<InputBindingsLoader>();
 
         // SAFETY: System sets must be configured before any system registration
+        app.configure_sets(Update, HyvoGridSet::Input);
         app.configure_sets(Update, HyvoGridSet::Setup);
         app.configure_sets(Update, HyvoGridSet::Physics);
         app.configure_sets(Update, HyvoGridSet::Rendering);
         app.configure_sets(Update, HyvoGridSet::ErrorHandling);
         
         // SAFETY: Set ordering must be explicit to prevent race conditions
+        app.configure_sets(Update, HyvoGridSet::Input.before(HyvoGridSet::Setup));
         app.configure_sets(Update, HyvoGridSet::Setup.before(HyvoGridSet::Physics));
         app.configure_sets(Update, HyvoGridSet::Physics.before(HyvoGridSet::Rendering));
         app.configure_sets(Update, HyvoGridSet::Rendering.before(HyvoGridSet::ErrorHandling));
 
+        // SAFETY: Steps nest in their grid set and are ordered against single systems separately
+        app.configure_sets(Update, HyvoGridStep::Selection.in_set(HyvoGridSet::Input));
+        app.configure_sets(Update, HyvoGridStep::Selection.after(track_node_hover));
+        app.configure_sets(Update, HyvoGridStep::Steering.in_set(HyvoGridSet::Physics));
+        app.configure_sets(Update, HyvoGridStep::Steering.before(integrate_cpu_particles));
+
         // SAFETY: Startup systems registered individually to prevent initialization order issues
         app.add_systems(Startup, setup_camera);
         app.add_systems(Startup, setup_materials);
         app.add_systems(Startup, setup_scene);
+        app.add_systems(Startup, load_themes);
+        app.add_systems(Startup, setup_selection_box);
+        app.add_systems(Startup, setup_cpu_particle_renderer);
+        app.add_systems(Startup, setup_particle_system);
+        app.add_systems(Startup, setup_node_effects);
+        app.add_systems(Startup, load_effect_definitions);
+        app.add_systems(Startup, setup_field_slice);
+        app.add_systems(Startup, load_camera_paths);
+        app.add_systems(Startup, setup_ortho_views);
+        app.add_systems(Startup, load_input_bindings);
+        app.add_systems(Startup, request_initial_helix);
+
+        // SAFETY: Theme systems run in Setup so materials are current before rendering
+        app.add_systems(Update, cycle_theme.in_set(HyvoGridSet::Setup));
+        app.add_systems(Update, apply_theme.in_set(HyvoGridSet::Setup));
+        app.add_systems(Update, report_theme_errors.in_set(HyvoGridSet::ErrorHandling));
+        app.add_systems(Update, apply_effect_definitions.in_set(HyvoGridSet::Setup));
+        app.add_systems(Update, report_effect_definition_errors.in_set(HyvoGridSet::ErrorHandling));
 
         // SAFETY: Physics systems must be registered individually with set assignment
         // DO NOT combine into tuple to avoid trait bound errors
-        app.add_systems(Update, generate_helix.in_set(HyvoGridSet::Physics));
         app.add_systems(Update, check_intersections.in_set(HyvoGridSet::Physics));
         app.add_systems(Update, update_magnetic_fields.in_set(HyvoGridSet::Physics));
+        app.add_systems(Update, spawn_cpu_particles.in_set(HyvoGridSet::Physics));
+        app.add_systems(Update, integrate_cpu_particles.in_set(HyvoGridSet::Physics));
+        app.add_systems(Update, steer_charged_particles.in_set(HyvoGridStep::Steering));
 
         // SAFETY: Rendering systems must be registered individually with set assignment
         // DO NOT combine into tuple to avoid trait bound errors
         app.add_systems(Update, update_node_visuals.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, update_node_effects.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, pulse_selected_nodes.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, update_interaction_effects.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, spawn_requested_emitters.in_set(HyvoGridSet::Setup));
         app.add_systems(Update, update_particles.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, update_node_outlines.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, render_cpu_particles.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, assign_cpu_backend.in_set(HyvoGridSet::Setup));
+        app.add_systems(Update, attach_node_effects.in_set(HyvoGridSet::Setup));
+        app.add_systems(Update, update_particle_budget.in_set(HyvoGridSet::Setup));
+
+        // SAFETY: Picking reactions read pointer events produced in PreUpdate
+        app.add_systems(Update, track_node_hover.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, update_selection.in_set(HyvoGridStep::Selection));
+        app.add_systems(Update, sync_selection.in_set(HyvoGridSet::Setup));
+
+        // SAFETY: Node manipulation registered individually; edits land before Physics reads them
+        app.add_systems(Update, drag_nodes.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, cycle_spawn_settings.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, spawn_node_at_cursor.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, delete_selected_nodes.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, toggle_node_polarity.in_set(HyvoGridSet::Input));
+
+        // SAFETY: Trails sample in Rendering so they see this frame's Physics movement
+        app.add_systems(Update, toggle_motion_trails.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, attach_motion_trails.in_set(HyvoGridSet::Setup));
+        app.add_systems(Update, record_motion_trails.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, draw_motion_trails.in_set(HyvoGridSet::Rendering));
+
+        // SAFETY: Field sampling reads node positions after Physics has moved them
+        app.add_systems(Update, control_field_visualization.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, drag_field_slice.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, update_field_slice.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, draw_field_glyphs.in_set(HyvoGridSet::Rendering));
+
+        // SAFETY: Camera targets change in Input; the camera moves before anything projects through it
+        app.add_systems(Update, orbit_camera_input.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, camera_bookmarks.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, frame_nodes.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, apply_orbit_camera.in_set(HyvoGridSet::Setup));
+        app.add_systems(Update, control_camera_path.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, play_camera_path.in_set(HyvoGridSet::Setup));
+
+        // SAFETY: Viewports lay out in Rendering, after Setup has settled this frame's camera targets
+        app.add_systems(Update, toggle_viewport_split.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, zoom_ortho_views.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, update_viewports.in_set(HyvoGridSet::Rendering));
+
+        // SAFETY: Bindings swap in Input; actions read this frame were resolved in PreUpdate
+        app.add_systems(Update, apply_input_bindings.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, report_input_binding_errors.in_set(HyvoGridSet::ErrorHandling));
+        app.add_systems(Update, take_screenshot.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, exit_on_action.in_set(HyvoGridSet::Input));
+
+        // SAFETY: The simulation clock is set in First, after Bevy advances time and before any Update system reads it
+        app.add_systems(First, sync_simulation_clock.after(TimeSystem));
+        app.add_systems(Update, control_simulation.in_set(HyvoGridSet::Input));
+        // SAFETY: Regeneration spawns in Setup so Physics sees a complete helix
+        app.add_systems(Update, generate_helix.in_set(HyvoGridSet::Setup));
+
+        // SAFETY: Snapshots record in Rendering, after Physics has finished the tick; restores land in Setup before Physics
+        app.add_systems(Startup, setup_timeline);
+        app.add_systems(Update, toggle_timeline.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, scrub_timeline.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, restore_snapshot.in_set(HyvoGridSet::Setup));
+        app.add_systems(Update, record_history.in_set(HyvoGridSet::Rendering));
+        app.add_systems(Update, update_timeline.in_set(HyvoGridSet::Rendering));
+
+        // SAFETY: The HUD reads statistics in Rendering, after Physics has produced this frame's
+        app.add_systems(Startup, setup_hud);
+        app.add_systems(Update, toggle_hud.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, update_hud.in_set(HyvoGridSet::Rendering));
+
+        // SAFETY: Captured input is released in PreUpdate, after leafwing resolves actions and before Input reads them
+        app.add_systems(PreUpdate, release_input_captured_by_inspector.after(InputManagerSystem::Update));
+        app.add_systems(Update, toggle_inspector.in_set(HyvoGridSet::Input));
+        app.add_systems(Update, inspector_ui.in_set(HyvoGridSet::Rendering));
 
         // SAFETY: Error handling system must run after all other systems
         app.add_systems(Update, error_check_system.in_set(HyvoGridSet::ErrorHandling));
@@ -171,8 +420,26 @@ mod tests {
 
     #[test]
     fn test_animation_state() {
-        let state = AnimationState::default();
+        let mut state = AnimationState::default();
         assert!(!state.paused);
         assert_eq!(state.speed, 1.0);
+
+        // Stepping only makes sense while paused
+        state.request_step();
+        assert!(!state.step_requested);
+        state.toggle_pause();
+        state.request_step();
+        assert!(state.step_requested);
+
+        for _ in 0..10 {
+            state.scale_speed(AnimationState::SPEED_STEP);
+        }
+        assert_eq!(state.speed, AnimationState::SPEED_LIMITS.1);
+
+        // Speeds set from outside, e.g. by the inspector, are clamped too
+        state.speed = -1.0;
+        assert!(!state.speed_in_limits());
+        state.clamp_speed();
+        assert_eq!(state.speed, AnimationState::SPEED_LIMITS.0);
     }
 }
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use crate::resources::CameraState;
+
+/// Number of bookmark slots, bound to the digit keys 1–9
+pub const BOOKMARK_SLOTS: usize = 9;
+
+/// A saved camera view
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct CameraBookmark {
+    pub name: String,
+    pub rotation: Quat,
+    pub radius: f32,
+    pub focus: Vec3,
+}
+
+impl CameraBookmark {
+    /// `state` with its targets replaced by this view
+    pub fn apply_to(&self, state: &mut CameraState) {
+        state.target_rotation = self.rotation;
+        state.target_radius = self.radius;
+        state.target_focus = self.focus;
+    }
+}
+
+/// Saved views in slots 1–9. Slots are numbered from one to match the keys.
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct CameraBookmarks {
+    slots: [Option<CameraBookmark>; BOOKMARK_SLOTS],
+}
+
+impl CameraBookmarks {
+    /// Save the targets of `state` into `slot`, keeping the slot's name if it has one
+    pub fn save(&mut self, slot: usize, state: &CameraState) -> Option<&CameraBookmark> {
+        let entry = self.slots.get_mut(slot.checked_sub(1)?)?;
+        let name = entry
+            .take()
+            .map(|bookmark| bookmark.name)
+            .unwrap_or_else(|| format!("Bookmark {slot}"));
+
+        *entry = Some(CameraBookmark {
+            name,
+            rotation: state.target_rotation,
+            radius: state.target_radius,
+            focus: state.target_focus,
+        });
+        entry.as_ref()
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&CameraBookmark> {
+        self.slots.get(slot.checked_sub(1)?)?.as_ref()
+    }
+
+    pub fn rename(&mut self, slot: usize, name: impl Into<String>) -> bool {
+        match slot.checked_sub(1).and_then(|index| self.slots.get_mut(index)) {
+            Some(Some(bookmark)) => {
+                bookmark.name = name.into();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn clear(&mut self, slot: usize) {
+        if let Some(entry) = slot.checked_sub(1).and_then(|index| self.slots.get_mut(index)) {
+            *entry = None;
+        }
+    }
+
+    /// Occupied slots with their numbers
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &CameraBookmark)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, bookmark)| Some((index + 1, bookmark.as_ref()?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bookmark_slots() {
+        let mut bookmarks = CameraBookmarks::default();
+        let mut state = CameraState {
+            target_focus: Vec3::new(1.0, 2.0, 3.0),
+            ..default()
+        };
+
+        assert!(bookmarks.save(0, &state).is_none());
+        assert!(bookmarks.save(10, &state).is_none());
+        assert_eq!(bookmarks.save(3, &state).unwrap().name, "Bookmark 3");
+        assert!(bookmarks.rename(3, "Strand gap"));
+        assert!(!bookmarks.rename(4, "Empty"));
+
+        // Re-saving keeps the name
+        state.target_radius = 5.0;
+        bookmarks.save(3, &state);
+        let saved = bookmarks.get(3).unwrap();
+        assert_eq!(saved.name, "Strand gap");
+        assert_eq!(saved.radius, 5.0);
+
+        let mut restored = CameraState::default();
+        saved.apply_to(&mut restored);
+        assert_eq!(restored.target_focus, state.target_focus);
+        assert_eq!(bookmarks.iter().count(), 1);
+    }
+}
@@ -0,0 +1,253 @@
+use bevy::{
+    prelude::*,
+    asset::{io::Reader, AssetLoader, LoadContext},
+};
+use serde::{Deserialize, Serialize};
+use crate::err::{Error, Result, ResourceError};
+
+/// Camera paths shipped under `assets/`
+pub const CAMERA_PATH_PATHS: &[&str] = &["camera_paths/helix_flythrough.camera_path.ron"];
+
+/// One pose on a camera path
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraKeyframe {
+    /// Seconds from the start of the path
+    pub time: f32,
+    pub position: Vec3,
+    pub look_at: Vec3,
+    /// Vertical field of view in degrees
+    pub fov: f32,
+}
+
+/// A camera pose sampled from a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPathSample {
+    pub position: Vec3,
+    pub look_at: Vec3,
+    /// Vertical field of view in radians
+    pub fov: f32,
+}
+
+impl CameraPathSample {
+    pub fn transform(&self) -> Transform {
+        Transform::from_translation(self.position).looking_at(self.look_at, Vec3::Y)
+    }
+}
+
+/// Keyframed camera path interpolated with Catmull-Rom splines. Sampling is a
+/// pure function of time, so the same clock always gives the same frames.
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct CameraPath {
+    pub name: String,
+    pub keyframes: Vec<CameraKeyframe>,
+    /// Wrap around after the last keyframe instead of holding it
+    #[serde(default)]
+    pub looped: bool,
+}
+
+impl CameraPath {
+    pub fn validate(&self) -> Result<()> {
+        if self.keyframes.len() < 2 {
+            return Err(ResourceError::InvalidConfig(format!("Camera path '{}' needs at least two keyframes", self.name)).into());
+        }
+        if self.keyframes[0].time != 0.0 {
+            return Err(ResourceError::InvalidConfig(format!("Camera path '{}' must start at time 0", self.name)).into());
+        }
+        if self.keyframes.windows(2).any(|pair| pair[1].time <= pair[0].time) {
+            return Err(ResourceError::InvalidConfig(format!("Camera path '{}' keyframe times must increase", self.name)).into());
+        }
+        for keyframe in &self.keyframes {
+            if !(1.0..=179.0).contains(&keyframe.fov) {
+                return Err(ResourceError::InvalidConfig(format!("Camera path '{}' FOV must be within 1..=179 degrees", self.name)).into());
+            }
+            if keyframe.position.distance(keyframe.look_at) < 1e-3 {
+                return Err(ResourceError::InvalidConfig(format!("Camera path '{}' looks at its own position", self.name)).into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// Pose at `time` seconds. Times past the end hold the last keyframe, or
+    /// wrap when `looped`.
+    pub fn sample(&self, time: f32) -> Option<CameraPathSample> {
+        let last = self.keyframes.len().checked_sub(1)?;
+        let duration = self.duration();
+        let time = if self.looped && duration > 0.0 {
+            time.rem_euclid(duration)
+        } else {
+            time.clamp(0.0, duration)
+        };
+
+        let segment = self.keyframes[..last]
+            .iter()
+            .rposition(|keyframe| keyframe.time <= time)
+            .unwrap_or(0);
+        let (k1, k2) = (&self.keyframes[segment], &self.keyframes[(segment + 1).min(last)]);
+        let span = k2.time - k1.time;
+        let t = if span > 0.0 { ((time - k1.time) / span).clamp(0.0, 1.0) } else { 0.0 };
+
+        // Neighbouring keys shape the tangents; ends reuse themselves
+        let k0 = &self.keyframes[segment.saturating_sub(1)];
+        let k3 = &self.keyframes[(segment + 2).min(last)];
+
+        Some(CameraPathSample {
+            position: catmull_rom(k0.position, k1.position, k2.position, k3.position, t),
+            look_at: catmull_rom(k0.look_at, k1.look_at, k2.look_at, k3.look_at, t),
+            fov: catmull_rom_scalar(k0.fov, k1.fov, k2.fov, k3.fov, t).clamp(1.0, 179.0).to_radians(),
+        })
+    }
+}
+
+/// Uniform Catmull-Rom between `p1` and `p2`
+pub fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * ((2.0 * p1)
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+fn catmull_rom_scalar(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
+    catmull_rom(Vec3::splat(p0), Vec3::splat(p1), Vec3::splat(p2), Vec3::splat(p3), t).x
+}
+
+/// Loads `.camera_path.ron` files into [`CameraPath`] assets
+#[derive(Default)]
+pub struct CameraPathLoader;
+
+impl AssetLoader for CameraPathLoader {
+    type Asset = CameraPath;
+    type Settings = ();
+    type Error = Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> std::result::Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(Error::from_io)?;
+        let path: CameraPath = ron::de::from_bytes(&bytes)
+            .map_err(|e| ResourceError::LoadFailed(format!("Invalid camera path RON: {e}")))?;
+
+        path.validate()?;
+        Ok(path)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["camera_path.ron"]
+    }
+}
+
+/// Which clock advances a playing path
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub enum PathClock {
+    /// Real time, regardless of simulation pause or speed
+    Wall,
+    /// Simulation time, so the camera stays in step with the nodes
+    #[default]
+    Simulation,
+    /// A fixed step per rendered frame, for offline capture
+    FixedStep(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum PathPlayback {
+    #[default]
+    Stopped,
+    Playing,
+    Paused,
+}
+
+/// Plays a `CameraPath` on the main camera
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct CameraPathPlayer {
+    pub paths: Vec<Handle<CameraPath>>,
+    /// Index into `paths` of the path to play
+    pub active: usize,
+    pub playback: PathPlayback,
+    pub clock: PathClock,
+    /// Seconds into the active path
+    pub time: f32,
+}
+
+impl CameraPathPlayer {
+    pub fn active_handle(&self) -> Option<&Handle<CameraPath>> {
+        self.paths.get(self.active)
+    }
+
+    pub fn play(&mut self) {
+        if self.playback == PathPlayback::Stopped {
+            self.time = 0.0;
+        }
+        self.playback = PathPlayback::Playing;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        match self.playback {
+            PathPlayback::Playing => self.playback = PathPlayback::Paused,
+            PathPlayback::Paused | PathPlayback::Stopped => self.play(),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.playback = PathPlayback::Stopped;
+        self.time = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyframe(time: f32, x: f32) -> CameraKeyframe {
+        CameraKeyframe {
+            time,
+            position: Vec3::new(x, 2.0, 10.0),
+            look_at: Vec3::ZERO,
+            fov: 45.0,
+        }
+    }
+
+    #[test]
+    fn test_camera_path_passes_through_keyframes() {
+        let path = CameraPath {
+            name: "test".to_string(),
+            keyframes: vec![keyframe(0.0, 0.0), keyframe(1.0, 4.0), keyframe(3.0, -2.0)],
+            looped: false,
+        };
+        assert!(path.validate().is_ok());
+
+        for key in &path.keyframes {
+            let sample = path.sample(key.time).unwrap();
+            assert!(sample.position.distance(key.position) < 1e-5);
+            assert!((sample.fov - 45f32.to_radians()).abs() < 1e-5);
+        }
+        // Holds the last pose past the end and is deterministic
+        assert_eq!(path.sample(10.0), path.sample(3.0));
+        assert_eq!(path.sample(1.7), path.sample(1.7));
+
+        let looped = CameraPath { looped: true, ..path.clone() };
+        assert!(looped.sample(4.0).unwrap().position.distance(looped.sample(1.0).unwrap().position) < 1e-5);
+
+        let unordered = CameraPath {
+            keyframes: vec![keyframe(0.0, 0.0), keyframe(0.0, 1.0)],
+            ..path
+        };
+        assert!(unordered.validate().is_err());
+    }
+
+    #[test]
+    fn test_shipped_camera_paths_parse() {
+        let path: CameraPath =
+            ron::de::from_str(include_str!("../../assets/camera_paths/helix_flythrough.camera_path.ron")).unwrap();
+        assert!(path.validate().is_ok());
+    }
+}
@@ -0,0 +1,252 @@
+use bevy::{math::Vec4, reflect::Reflect};
+use bevy_hanabi::Gradient;
+use crate::{
+    err::{Result, ResourceError},
+    resources::uni_color::UniColor,
+};
+
+/// Number of keys used when baking a colormap into a hanabi gradient.
+/// Hanabi interpolates linearly in RGB between keys, so the map is resampled
+/// densely enough to keep the OKLab interpolation visible on the GPU.
+pub const GRADIENT_SAMPLES: usize = 16;
+
+/// Predefined colormaps for visual encodings of field data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum ColormapPreset {
+    /// Perceptually uniform, blue-green-yellow
+    Viridis,
+    /// Perceptually uniform, black-purple-cream
+    Magma,
+    /// Perceptually uniform, blue-purple-yellow
+    Plasma,
+    /// Perceptually uniform and colour-vision-deficiency friendly
+    Cividis,
+    /// Diverging map for signed quantities: repulsion (red) to attraction (blue)
+    AttractionRepulsion,
+}
+
+/// A colormap built from sorted `(position, color)` stops in `0.0..=1.0`.
+///
+/// Colors between stops are interpolated in OKLab. Diverging maps treat
+/// `0.5` as the neutral midpoint and are normally sampled with
+/// [`Colormap::sample_signed`].
+#[derive(Debug, Clone)]
+pub struct Colormap {
+    stops: Vec<(f32, UniColor)>,
+    diverging: bool,
+}
+
+impl Colormap {
+    /// Create a colormap from custom gradient stops
+    pub fn custom(mut stops: Vec<(f32, UniColor)>) -> Result<Self> {
+        if stops.len() < 2 {
+            return Err(ResourceError::InvalidConfig("Colormap needs at least two stops".to_string()).into());
+        }
+        if stops.iter().any(|(pos, _)| !pos.is_finite() || *pos < 0.0 || *pos > 1.0) {
+            return Err(ResourceError::InvalidConfig("Colormap stop positions must be within 0.0..=1.0".to_string()).into());
+        }
+        if stops.iter().any(|(_, color)| !color.is_valid()) {
+            return Err(ResourceError::InvalidConfig("Colormap stop colors must be valid".to_string()).into());
+        }
+
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self { stops, diverging: false })
+    }
+
+    /// Create a diverging colormap through a neutral midpoint
+    pub fn diverging(negative: UniColor, neutral: UniColor, positive: UniColor) -> Self {
+        Self {
+            stops: vec![(0.0, negative), (0.5, neutral), (1.0, positive)],
+            diverging: true,
+        }
+    }
+
+    pub fn preset(preset: ColormapPreset) -> Self {
+        match preset {
+            ColormapPreset::Viridis => Self::viridis(),
+            ColormapPreset::Magma => Self::magma(),
+            ColormapPreset::Plasma => Self::plasma(),
+            ColormapPreset::Cividis => Self::cividis(),
+            ColormapPreset::AttractionRepulsion => Self::attraction_repulsion(),
+        }
+    }
+
+    pub fn viridis() -> Self {
+        Self::from_hex(&[
+            0x440154, 0x472c7a, 0x3b518b, 0x2c718e, 0x21908d,
+            0x27ad81, 0x5cc863, 0xaadc32, 0xfde725,
+        ])
+    }
+
+    pub fn magma() -> Self {
+        Self::from_hex(&[
+            0x000004, 0x1c1044, 0x4f127b, 0x812581, 0xb5367a,
+            0xe55064, 0xfb8761, 0xfec287, 0xfcfdbf,
+        ])
+    }
+
+    pub fn plasma() -> Self {
+        Self::from_hex(&[
+            0x0d0887, 0x4c02a1, 0x7e03a8, 0xa92395, 0xcc4778,
+            0xe56b5d, 0xf89441, 0xfdc328, 0xf0f921,
+        ])
+    }
+
+    pub fn cividis() -> Self {
+        Self::from_hex(&[
+            0x00224e, 0x1f3a6e, 0x434e6c, 0x5f636f, 0x7b7a77,
+            0x989279, 0xb7ab71, 0xd8c660, 0xfee838,
+        ])
+    }
+
+    /// Diverging map for interaction strength: negative values (repulsion) are
+    /// red, positive values (attraction) are blue, matching `MagneticField::get_color`
+    pub fn attraction_repulsion() -> Self {
+        Self::diverging(
+            UniColor::srgb(1.0, 0.2, 0.0),
+            UniColor::srgb(0.92, 0.92, 0.92),
+            UniColor::srgb(0.0, 0.5, 1.0),
+        )
+    }
+
+    /// Evenly spaced stops from packed 0xRRGGBB values
+    fn from_hex(colors: &[u32]) -> Self {
+        let last = (colors.len() - 1) as f32;
+        Self {
+            stops: colors
+                .iter()
+                .enumerate()
+                .map(|(i, rgb)| (i as f32 / last, UniColor::hex(*rgb)))
+                .collect(),
+            diverging: false,
+        }
+    }
+
+    pub fn is_diverging(&self) -> bool {
+        self.diverging
+    }
+
+    pub fn stops(&self) -> &[(f32, UniColor)] {
+        &self.stops
+    }
+
+    /// Sample the colormap at `t` in `0.0..=1.0`
+    pub fn sample(&self, t: f32) -> UniColor {
+        let t = if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.0 };
+
+        let (first_pos, first_color) = self.stops[0];
+        if t <= first_pos {
+            return first_color;
+        }
+
+        for window in self.stops.windows(2) {
+            let (start_pos, start_color) = window[0];
+            let (end_pos, end_color) = window[1];
+            if t <= end_pos {
+                let span = end_pos - start_pos;
+                let local = if span > f32::EPSILON { (t - start_pos) / span } else { 1.0 };
+                return start_color.lerp_oklab(end_color, local);
+            }
+        }
+
+        self.stops[self.stops.len() - 1].1
+    }
+
+    /// Sample a value in `min..=max`
+    pub fn sample_range(&self, value: f32, min: f32, max: f32) -> UniColor {
+        let span = max - min;
+        if span.abs() <= f32::EPSILON {
+            return self.sample(0.5);
+        }
+        self.sample((value - min) / span)
+    }
+
+    /// Sample a signed value in `-max_abs..=max_abs` so that zero maps to the midpoint
+    pub fn sample_signed(&self, value: f32, max_abs: f32) -> UniColor {
+        self.sample_range(value, -max_abs.abs(), max_abs.abs())
+    }
+
+    /// Bake the colormap into a hanabi gradient using [`GRADIENT_SAMPLES`] keys
+    pub fn to_gradient(&self) -> Gradient<Vec4> {
+        self.to_gradient_with_samples(GRADIENT_SAMPLES)
+    }
+
+    /// Bake the colormap into a hanabi gradient with an explicit key count
+    pub fn to_gradient_with_samples(&self, samples: usize) -> Gradient<Vec4> {
+        let samples = samples.max(2);
+        let mut gradient = Gradient::new();
+        for i in 0..samples {
+            let t = i as f32 / (samples - 1) as f32;
+            gradient.add_key(t, self.sample(t).as_vec4());
+        }
+        gradient
+    }
+
+    /// Bake the colormap into a hanabi gradient whose alpha fades to zero over
+    /// particle lifetime, for use with `ColorOverLifetimeModifier`
+    pub fn to_fading_gradient(&self) -> Gradient<Vec4> {
+        let mut gradient = Gradient::new();
+        for i in 0..GRADIENT_SAMPLES {
+            let t = i as f32 / (GRADIENT_SAMPLES - 1) as f32;
+            gradient.add_key(t, self.sample(t).with_alpha(1.0 - t).as_vec4());
+        }
+        gradient
+    }
+}
+
+impl Default for Colormap {
+    fn default() -> Self {
+        Self::viridis()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: UniColor, b: UniColor) -> bool {
+        (a.r - b.r).abs() < 0.01 && (a.g - b.g).abs() < 0.01 && (a.b - b.b).abs() < 0.01
+    }
+
+    #[test]
+    fn test_colour_space_round_trips() {
+        let color = UniColor::srgb(0.2, 0.6, 0.4);
+
+        let (h, s, v) = color.to_hsv();
+        assert!(approx(UniColor::hsv(h, s, v), color));
+
+        let (h, s, l) = color.to_hsl();
+        assert!(approx(UniColor::hsl(h, s, l), color));
+
+        let (l, a, b) = color.to_oklab();
+        assert!(approx(UniColor::oklab(l, a, b), color));
+    }
+
+    #[test]
+    fn test_sample_endpoints() {
+        let map = Colormap::viridis();
+        assert!(approx(map.sample(0.0), UniColor::hex(0x440154)));
+        assert!(approx(map.sample(1.0), UniColor::hex(0xfde725)));
+        assert!(approx(map.sample(-1.0), map.sample(0.0)));
+        assert!(approx(map.sample(f32::NAN), map.sample(0.0)));
+    }
+
+    #[test]
+    fn test_signed_sampling_is_centred() {
+        let map = Colormap::attraction_repulsion();
+        assert!(map.is_diverging());
+        assert!(approx(map.sample_signed(0.0, 3.0), UniColor::srgb(0.92, 0.92, 0.92)));
+        assert!(approx(map.sample_signed(3.0, 3.0), UniColor::srgb(0.0, 0.5, 1.0)));
+        assert!(approx(map.sample_signed(-3.0, 3.0), UniColor::srgb(1.0, 0.2, 0.0)));
+    }
+
+    #[test]
+    fn test_custom_stops_are_validated_and_sorted() {
+        assert!(Colormap::custom(vec![(0.0, UniColor::red())]).is_err());
+        assert!(Colormap::custom(vec![(0.0, UniColor::red()), (1.5, UniColor::blue())]).is_err());
+
+        let map = Colormap::custom(vec![(1.0, UniColor::blue()), (0.0, UniColor::red())]).unwrap();
+        assert_eq!(map.stops()[0].0, 0.0);
+        assert!(approx(map.sample(0.0), UniColor::red()));
+    }
+}
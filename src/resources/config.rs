@@ -1,11 +1,19 @@
 use bevy::prelude::*;
-use crate::err::{Result, ResourceError};
+use crate::{
+    components::{Polarity, ShapeType},
+    err::{Result, ResourceError},
+    resources::colormap::ColormapPreset,
+};
 
-/// Animation state configuration
-#[derive(Resource)]
+/// Simulation pause and speed. Applied to `Time<Virtual>` each frame, so every
+/// `Update` system reading `Res<Time>` follows them; see `systems::simulation`.
+#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[reflect(Resource)]
 pub struct AnimationState {
     pub paused: bool,
     pub speed: f32,
+    /// Advance exactly one `STEP` on the next frame; only honoured while paused
+    pub step_requested: bool,
 }
 
 impl Default for AnimationState {
@@ -13,34 +21,284 @@ impl Default for AnimationState {
         Self {
             paused: false,
             speed: 1.0,
+            step_requested: false,
         }
     }
 }
 
-/// Camera configuration state
-#[derive(Resource)]
+impl AnimationState {
+    /// Slowest and fastest simulation speed
+    pub const SPEED_LIMITS: (f32, f32) = (0.125, 8.0);
+    /// Speed multiplier per speed up / speed down
+    pub const SPEED_STEP: f32 = 2.0;
+    /// Seconds of simulation time in one single-stepped tick
+    pub const STEP: f32 = 1.0 / 60.0;
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.step_requested = false;
+    }
+
+    /// Multiply the speed by `factor`, within `SPEED_LIMITS`
+    pub fn scale_speed(&mut self, factor: f32) {
+        self.speed *= factor;
+        self.clamp_speed();
+    }
+
+    /// Bring a speed set from outside, such as the inspector, back within
+    /// `SPEED_LIMITS`; a NaN speed resets to normal speed
+    pub fn clamp_speed(&mut self) {
+        let (min, max) = Self::SPEED_LIMITS;
+        self.speed = if self.speed.is_nan() { 1.0 } else { self.speed.clamp(min, max) };
+    }
+
+    pub fn speed_in_limits(&self) -> bool {
+        let (min, max) = Self::SPEED_LIMITS;
+        (min..=max).contains(&self.speed)
+    }
+
+    pub fn request_step(&mut self) {
+        self.step_requested = self.paused;
+    }
+}
+
+/// Where the orbit camera is heading. Input edits these targets and the
+/// camera eases toward them; see `systems::camera`.
+#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[reflect(Resource)]
 pub struct CameraState {
-    pub target_yaw: f32,
-    pub target_pitch: f32,
+    /// Orientation of the camera around its focus; the camera sits along local +Z
+    pub target_rotation: Quat,
     pub target_radius: f32,
     pub target_focus: Vec3,
-    pub transition_time: f32,
+    /// Rate at which the camera closes the gap to its targets, per second
+    pub smoothing: f32,
 }
 
 impl Default for CameraState {
     fn default() -> Self {
         Self {
-            target_yaw: -std::f32::consts::FRAC_PI_4,
-            target_pitch: std::f32::consts::FRAC_PI_6,
+            target_rotation: Self::rotation_from_yaw_pitch(
+                -std::f32::consts::FRAC_PI_4,
+                std::f32::consts::FRAC_PI_6,
+            ),
             target_radius: 20.0,
             target_focus: Vec3::ZERO,
-            transition_time: 0.0,
+            smoothing: 12.0,
+        }
+    }
+}
+
+impl CameraState {
+    /// Nearest and furthest the camera may get to its focus
+    pub const RADIUS_LIMITS: (f32, f32) = (0.5, 200.0);
+
+    /// Orientation looking down at the focus from `pitch` above the horizon
+    pub fn rotation_from_yaw_pitch(yaw: f32, pitch: f32) -> Quat {
+        Quat::from_rotation_y(yaw) * Quat::from_rotation_x(-pitch)
+    }
+
+    /// Yaw about the world up axis, then pitch about the camera's own right
+    /// axis. Composing quaternions keeps the camera free to pass over the poles.
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.target_rotation =
+            (Quat::from_rotation_y(yaw) * self.target_rotation * Quat::from_rotation_x(-pitch)).normalize();
+    }
+
+    /// Move the focus in the camera plane; `delta` is in units of the orbit radius
+    pub fn pan(&mut self, delta: Vec2) {
+        let right = self.target_rotation * Vec3::X;
+        let up = self.target_rotation * Vec3::Y;
+        self.target_focus += (right * delta.x + up * delta.y) * self.target_radius;
+    }
+
+    /// Scale the orbit radius by `factor`, within `RADIUS_LIMITS`
+    pub fn zoom(&mut self, factor: f32) {
+        let (min, max) = Self::RADIUS_LIMITS;
+        self.target_radius = (self.target_radius * factor).clamp(min, max);
+    }
+
+    /// Camera transform for the given focus, rotation and radius
+    pub fn transform_for(focus: Vec3, rotation: Quat, radius: f32) -> Transform {
+        Transform {
+            translation: focus + rotation * Vec3::new(0.0, 0.0, radius),
+            rotation,
+            ..default()
+        }
+    }
+
+    pub fn target_transform(&self) -> Transform {
+        Self::transform_for(self.target_focus, self.target_rotation, self.target_radius)
+    }
+}
+
+/// Shape and polarity used when spawning nodes interactively
+#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[reflect(Resource)]
+pub struct NodeSpawnSettings {
+    pub shape_type: ShapeType,
+    pub polarity: Polarity,
+}
+
+impl Default for NodeSpawnSettings {
+    fn default() -> Self {
+        Self {
+            shape_type: ShapeType::Alpha,
+            polarity: Polarity::North,
         }
     }
 }
 
+impl NodeSpawnSettings {
+    pub fn cycle_shape(&mut self) {
+        self.shape_type = match self.shape_type {
+            ShapeType::Alpha => ShapeType::Beta,
+            ShapeType::Beta => ShapeType::Gamma,
+            ShapeType::Gamma => ShapeType::Alpha,
+        };
+    }
+}
+
+/// Which path simulates newly spawned `ParticleEmitter`s. `Cpu` spawns them
+/// without a hanabi effect so the CPU particle systems take them; only those
+/// particles are charged and steered by node fields, and they also run without
+/// a GPU, e.g. in headless tests. `Gpu` is cheaper but ignores the fields.
+/// Picked at startup from `HYVO_PARTICLE_BACKEND` and switchable in the inspector.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Resource)]
+pub enum ParticleBackend {
+    Gpu,
+    #[default]
+    Cpu,
+}
+
+impl ParticleBackend {
+    /// Environment variable naming the backend at startup, `cpu` or `gpu`
+    pub const ENV_VAR: &'static str = "HYVO_PARTICLE_BACKEND";
+
+    /// The backend named by `ENV_VAR`, or the default when it is unset
+    pub fn from_env() -> Result<Self> {
+        match std::env::var(Self::ENV_VAR) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+impl std::str::FromStr for ParticleBackend {
+    type Err = crate::err::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "cpu" => Ok(Self::Cpu),
+            "gpu" => Ok(Self::Gpu),
+            other => Err(ResourceError::InvalidConfig(format!(
+                "Unknown particle backend '{other}'; expected cpu or gpu"
+            ))
+            .into()),
+        }
+    }
+}
+
+/// Parameters for steering charged particles through the node magnetic fields
+#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[reflect(Resource)]
+pub struct LorentzConfig {
+    /// Charge-to-mass ratio q/m applied to every `ChargedParticle`
+    pub charge_to_mass: f32,
+    /// Only this many nearest nodes contribute to the field at a particle
+    pub max_influencing_nodes: usize,
+    /// Nodes further than this are ignored entirely
+    pub influence_radius: f32,
+}
+
+impl Default for LorentzConfig {
+    fn default() -> Self {
+        Self {
+            charge_to_mass: 4.0,
+            max_influencing_nodes: 4,
+            influence_radius: 6.0,
+        }
+    }
+}
+
+impl LorentzConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !self.charge_to_mass.is_finite() {
+            return Err(ResourceError::InvalidConfig("Charge-to-mass ratio must be finite".to_string()).into());
+        }
+        if self.max_influencing_nodes == 0 {
+            return Err(ResourceError::InvalidConfig("At least one node must influence particles".to_string()).into());
+        }
+        if self.influence_radius <= 0.0 {
+            return Err(ResourceError::InvalidConfig("Influence radius must be positive".to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+/// How motion trail segments are coloured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum TrailColorMode {
+    /// Sample `TrailSettings::colormap` by speed relative to `max_speed`
+    #[default]
+    Speed,
+    /// Use the node's polarity colour
+    Polarity,
+}
+
+/// Motion trails drawn behind moving nodes
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct TrailSettings {
+    pub enabled: bool,
+    /// Number of samples kept per node
+    pub length: usize,
+    /// Seconds between recorded samples
+    pub sample_interval: f32,
+    pub color_mode: TrailColorMode,
+    pub colormap: ColormapPreset,
+    /// Speed mapped to the top of the colormap
+    pub max_speed: f32,
+    /// Nodes moving less than this between samples do not record a new one
+    pub min_distance: f32,
+}
+
+impl Default for TrailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            length: 64,
+            sample_interval: 0.05,
+            color_mode: TrailColorMode::Speed,
+            colormap: ColormapPreset::Viridis,
+            max_speed: 2.0,
+            min_distance: 0.001,
+        }
+    }
+}
+
+impl TrailSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.length < 2 {
+            return Err(ResourceError::InvalidConfig("Trails need at least two samples".to_string()).into());
+        }
+        if self.sample_interval <= 0.0 || !self.sample_interval.is_finite() {
+            return Err(ResourceError::InvalidConfig("Trail sample interval must be positive".to_string()).into());
+        }
+        if self.max_speed <= 0.0 || !self.max_speed.is_finite() {
+            return Err(ResourceError::InvalidConfig("Trail max speed must be positive".to_string()).into());
+        }
+        if self.min_distance < 0.0 {
+            return Err(ResourceError::InvalidConfig("Trail min distance cannot be negative".to_string()).into());
+        }
+        Ok(())
+    }
+}
+
 /// Configuration for HyvoGrid plugins
-#[derive(Resource, Clone)]
+#[derive(Resource, Clone, Reflect)]
+#[reflect(Resource)]
 pub struct HyvoGridConfig {
     pub enable_hanabi: bool,
     pub enable_tweening: bool,
@@ -67,12 +325,22 @@ impl HyvoGridConfig {
 }
 
 /// General simulation configuration
-#[derive(Resource, Default)]
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
 pub struct SimulationConfig {
     pub simulation_speed: f32,
     pub particle_density: f32,
 }
 
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            simulation_speed: 1.0,
+            particle_density: 1.0,
+        }
+    }
+}
+
 impl SimulationConfig {
     pub fn validate(&self) -> Result<()> {
         if self.simulation_speed <= 0.0 {
@@ -0,0 +1,409 @@
+use bevy::{
+    prelude::*,
+    asset::{io::Reader, AssetLoader, LoadContext},
+    utils::HashMap,
+};
+use bevy_hanabi::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::{
+    err::{Error, Result, ResourceError},
+    resources::{effects::EffectKind, theme::Theme, uni_color::UniColor},
+    systems::particles::{EffectUpdateModifier, EmitterInitModifier},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SpawnerDefinition {
+    Rate(f32),
+    Once { count: f32, immediate: bool },
+    Burst { count: f32, period: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PropertyDefault {
+    Float(f32),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+}
+
+/// A named effect property that systems set per instance through `EffectProperties`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyDefinition {
+    pub name: String,
+    pub default: PropertyDefault,
+}
+
+/// Init modifiers, applied in file order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InitDefinition {
+    Age(f32),
+    Lifetime(f32),
+    PositionSphere { radius: f32, surface: bool },
+    PositionCircle { radius: f32, axis: [f32; 3], surface: bool },
+    PositionCone { base_radius: f32, top_radius: f32, height: f32 },
+    VelocitySphere { speed: f32 },
+    VelocityTangent { axis: [f32; 3], speed: f32 },
+    /// Colour from a `Vec4` property
+    ColorFromProperty(String),
+    /// Size from a float property
+    SizeFromProperty(String),
+    /// Kill particles at birth unless a random draw falls below the float
+    /// property; thins emission without touching the spawner. Place after `Lifetime`.
+    SpawnFraction(String),
+    /// Flow towards the `target` offset when `attract` is 1, living just long
+    /// enough to arrive, or splay away from it when `attract` is 0
+    InteractionStream { target: String, attract: String, speed: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum UpdateDefinition {
+    LinearDrag(f32),
+    Accel([f32; 3]),
+    RadialAccel(f32),
+    ConformToSphere {
+        radius: f32,
+        influence_dist: f32,
+        attraction_accel: f32,
+        max_attraction_speed: f32,
+        sticky_factor: f32,
+        shell_half_thickness: f32,
+    },
+}
+
+/// Theme colours that replace a definition's own gradient
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ThemeGradient {
+    North,
+    South,
+    Particles,
+}
+
+/// A hanabi effect described as data, loaded from `.effect.ron` and hot reloaded
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct EffectDefinition {
+    pub name: String,
+    pub capacity: u32,
+    pub spawner: SpawnerDefinition,
+    #[serde(default)]
+    pub properties: Vec<PropertyDefinition>,
+    #[serde(default)]
+    pub init: Vec<InitDefinition>,
+    #[serde(default)]
+    pub update: Vec<UpdateDefinition>,
+    #[serde(default)]
+    pub color_gradient: Vec<(f32, UniColor)>,
+    #[serde(default)]
+    pub theme_gradient: Option<ThemeGradient>,
+    #[serde(default)]
+    pub size_gradient: Vec<(f32, f32)>,
+}
+
+impl EffectDefinition {
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| -> Result<()> {
+            Err(ResourceError::ValidationFailed(format!("Effect '{}': {message}", self.name)).into())
+        };
+
+        if self.capacity == 0 {
+            return invalid("capacity must be positive".into());
+        }
+        let spawner_valid = match self.spawner {
+            SpawnerDefinition::Rate(rate) => rate.is_finite() && rate >= 0.0,
+            SpawnerDefinition::Once { count, .. } => count.is_finite() && count >= 0.0,
+            SpawnerDefinition::Burst { count, period } => count.is_finite() && count >= 0.0 && period > 0.0,
+        };
+        if !spawner_valid {
+            return invalid("spawner values must be finite and non-negative".into());
+        }
+        if self.color_gradient.iter().any(|(t, color)| !(0.0..=1.0).contains(t) || !color.is_valid()) {
+            return invalid("colour gradient keys must be within 0.0..=1.0 with valid colours".into());
+        }
+        if self.size_gradient.iter().any(|(t, size)| !(0.0..=1.0).contains(t) || *size < 0.0) {
+            return invalid("size gradient keys must be within 0.0..=1.0 with non-negative sizes".into());
+        }
+
+        for init in self.init.iter() {
+            let referenced: Vec<&String> = match init {
+                InitDefinition::ColorFromProperty(name)
+                | InitDefinition::SizeFromProperty(name)
+                | InitDefinition::SpawnFraction(name) => vec![name],
+                InitDefinition::InteractionStream { target, attract, .. } => vec![target, attract],
+                _ => Vec::new(),
+            };
+            for name in referenced {
+                if !self.properties.iter().any(|property| &property.name == name) {
+                    return invalid(format!("init modifier uses undeclared property '{name}'"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the hanabi effect, taking the gradient from `theme` when requested
+    pub fn build(&self, theme: &Theme) -> Result<EffectAsset> {
+        self.validate()?;
+
+        let writer = ExprWriter::new();
+        let properties: HashMap<&str, WriterExpr> = self
+            .properties
+            .iter()
+            .map(|property| {
+                let value = match property.default {
+                    PropertyDefault::Float(value) => value.into(),
+                    PropertyDefault::Vec3(value) => Vec3::from(value).into(),
+                    PropertyDefault::Vec4(value) => Vec4::from(value).into(),
+                };
+                let handle = writer.add_property(property.name.clone(), value);
+                (property.name.as_str(), writer.prop(handle))
+            })
+            .collect();
+        // Validation guarantees every referenced property exists
+        let prop = |name: &String| properties[name.as_str()].clone();
+
+        let init: Vec<EmitterInitModifier> = self
+            .init
+            .iter()
+            .map(|init| init_modifier(init, &writer, &prop))
+            .collect();
+        let update: Vec<EffectUpdateModifier> = self
+            .update
+            .iter()
+            .map(|update| update_modifier(update, &writer))
+            .collect();
+
+        let spawner = match self.spawner {
+            SpawnerDefinition::Rate(rate) => Spawner::rate(rate.into()),
+            SpawnerDefinition::Once { count, immediate } => Spawner::once(count.into(), immediate),
+            SpawnerDefinition::Burst { count, period } => Spawner::burst(count.into(), period.into()),
+        };
+
+        let mut effect = EffectAsset::new(self.capacity, spawner, writer.finish()).with_name(self.name.clone());
+        for modifier in init {
+            effect = modifier.attach(effect);
+        }
+        for modifier in update {
+            effect = modifier.attach(effect);
+        }
+
+        if !self.size_gradient.is_empty() {
+            let mut gradient = Gradient::new();
+            for (t, size) in self.size_gradient.iter() {
+                gradient.add_key(*t, Vec3::splat(*size));
+            }
+            effect = effect.render(SizeOverLifetimeModifier {
+                gradient,
+                screen_space_size: false,
+            });
+        }
+
+        if let Some(gradient) = self.color_gradient(theme) {
+            effect = effect.render(ColorOverLifetimeModifier { gradient });
+        }
+        Ok(effect)
+    }
+
+    /// Colour over lifetime, or `None` to keep the colour set at init
+    pub fn color_gradient(&self, theme: &Theme) -> Option<Gradient<Vec4>> {
+        match self.theme_gradient {
+            Some(ThemeGradient::North) => Some(theme.polarity_gradient(true)),
+            Some(ThemeGradient::South) => Some(theme.polarity_gradient(false)),
+            Some(ThemeGradient::Particles) => Some(theme.particle_effect_gradient()),
+            None if self.color_gradient.is_empty() => None,
+            None => {
+                let mut gradient = Gradient::new();
+                for (t, color) in self.color_gradient.iter() {
+                    gradient.add_key(*t, color.as_vec4());
+                }
+                Some(gradient)
+            }
+        }
+    }
+}
+
+fn init_modifier(
+    init: &InitDefinition,
+    writer: &ExprWriter,
+    prop: &impl Fn(&String) -> WriterExpr,
+) -> EmitterInitModifier {
+    let dimension = |surface: bool| if surface { ShapeDimension::Surface } else { ShapeDimension::Volume };
+
+    match init {
+        InitDefinition::Age(age) => {
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::AGE, writer.lit(*age).expr()))
+        }
+        InitDefinition::Lifetime(lifetime) => {
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::LIFETIME, writer.lit(*lifetime).expr()))
+        }
+        InitDefinition::PositionSphere { radius, surface } => EmitterInitModifier::PositionSphere(SetPositionSphereModifier {
+            center: writer.lit(Vec3::ZERO).expr(),
+            radius: writer.lit(*radius).expr(),
+            dimension: dimension(*surface),
+        }),
+        InitDefinition::PositionCircle { radius, axis, surface } => EmitterInitModifier::PositionCircle(SetPositionCircleModifier {
+            center: writer.lit(Vec3::ZERO).expr(),
+            axis: writer.lit(Vec3::from(*axis)).expr(),
+            radius: writer.lit(*radius).expr(),
+            dimension: dimension(*surface),
+        }),
+        InitDefinition::PositionCone { base_radius, top_radius, height } => EmitterInitModifier::PositionCone(SetPositionCone3dModifier {
+            base_radius: writer.lit(*base_radius).expr(),
+            top_radius: writer.lit(*top_radius).expr(),
+            height: writer.lit(*height).expr(),
+            dimension: ShapeDimension::Volume,
+        }),
+        InitDefinition::VelocitySphere { speed } => EmitterInitModifier::VelocitySphere(SetVelocitySphereModifier {
+            center: writer.lit(Vec3::ZERO).expr(),
+            speed: writer.lit(*speed).expr(),
+        }),
+        InitDefinition::VelocityTangent { axis, speed } => EmitterInitModifier::VelocityTangent(SetVelocityTangentModifier {
+            origin: writer.lit(Vec3::ZERO).expr(),
+            axis: writer.lit(Vec3::from(*axis)).expr(),
+            speed: writer.lit(*speed).expr(),
+        }),
+        InitDefinition::ColorFromProperty(name) => {
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::COLOR, prop(name).pack4x8unorm().expr()))
+        }
+        InitDefinition::SizeFromProperty(name) => {
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::SIZE, prop(name).expr()))
+        }
+        InitDefinition::SpawnFraction(name) => {
+            let alive = writer.rand(ScalarType::Float).lt(prop(name)).cast(ScalarType::Float);
+            let lifetime = writer.attr(Attribute::LIFETIME) * alive;
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::LIFETIME, lifetime.expr()))
+        }
+        InitDefinition::InteractionStream { target, attract, speed } => {
+            let position = writer.attr(Attribute::POSITION);
+            let toward = (prop(target) - position.clone()).normalized();
+            let away = (position.normalized() - prop(target).normalized()).normalized();
+            let direction = toward * prop(attract) + away * (writer.lit(1.0) - prop(attract));
+            let velocity = direction.normalized() * writer.lit(*speed);
+
+            // Attracting particles live just long enough to reach the target
+            let travel_time = prop(target).length() / writer.lit(*speed);
+            let lifetime = travel_time * prop(attract) + writer.attr(Attribute::LIFETIME) * (writer.lit(1.0) - prop(attract));
+
+            EmitterInitModifier::Attributes(vec![
+                SetAttributeModifier::new(Attribute::VELOCITY, velocity.expr()),
+                SetAttributeModifier::new(Attribute::LIFETIME, lifetime.expr()),
+            ])
+        }
+    }
+}
+
+fn update_modifier(update: &UpdateDefinition, writer: &ExprWriter) -> EffectUpdateModifier {
+    match *update {
+        UpdateDefinition::LinearDrag(drag) => {
+            EffectUpdateModifier::LinearDrag(LinearDragModifier::new(writer.lit(drag).expr()))
+        }
+        UpdateDefinition::Accel(accel) => {
+            EffectUpdateModifier::Accel(AccelModifier::new(writer.lit(Vec3::from(accel)).expr()))
+        }
+        UpdateDefinition::RadialAccel(accel) => EffectUpdateModifier::RadialAccel(RadialAccelModifier::new(
+            writer.lit(Vec3::ZERO).expr(),
+            writer.lit(accel).expr(),
+        )),
+        UpdateDefinition::ConformToSphere {
+            radius,
+            influence_dist,
+            attraction_accel,
+            max_attraction_speed,
+            sticky_factor,
+            shell_half_thickness,
+        } => EffectUpdateModifier::ConformToSphere(ConformToSphereModifier {
+            origin: writer.lit(Vec3::ZERO).expr(),
+            radius: writer.lit(radius).expr(),
+            influence_dist: writer.lit(influence_dist).expr(),
+            attraction_accel: writer.lit(attraction_accel).expr(),
+            max_attraction_speed: writer.lit(max_attraction_speed).expr(),
+            sticky_factor: Some(writer.lit(sticky_factor).expr()),
+            shell_half_thickness: Some(writer.lit(shell_half_thickness).expr()),
+        }),
+    }
+}
+
+/// Loads `.effect.ron` files into [`EffectDefinition`] assets
+#[derive(Default)]
+pub struct EffectDefinitionLoader;
+
+impl AssetLoader for EffectDefinitionLoader {
+    type Asset = EffectDefinition;
+    type Settings = ();
+    type Error = Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> std::result::Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(Error::from_io)?;
+        let definition: EffectDefinition = ron::de::from_bytes(&bytes)
+            .map_err(|e| ResourceError::LoadFailed(format!("Invalid effect RON: {e}")))?;
+
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["effect.ron"]
+    }
+}
+
+/// Loaded definition handles per effect kind. Custom kinds may add their own.
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct EffectLibrary {
+    pub definitions: HashMap<EffectKind, Handle<EffectDefinition>>,
+    /// Set when every loaded definition must be rebuilt, e.g. after a theme change
+    pub dirty: bool,
+}
+
+impl EffectLibrary {
+    pub fn kind_of(&self, id: AssetId<EffectDefinition>) -> Option<EffectKind> {
+        self.definitions
+            .iter()
+            .find(|(_, handle)| handle.id() == id)
+            .map(|(kind, _)| *kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_effects_parse_and_validate() {
+        let sources = [
+            include_str!("../../assets/effects/north_pole.effect.ron"),
+            include_str!("../../assets/effects/south_pole.effect.ron"),
+            include_str!("../../assets/effects/interaction.effect.ron"),
+            include_str!("../../assets/effects/node_active.effect.ron"),
+            include_str!("../../assets/effects/node_highlight.effect.ron"),
+            include_str!("../../assets/effects/node_pulse.effect.ron"),
+            include_str!("../../assets/effects/fallback.effect.ron"),
+            include_str!("../../assets/effects/collision.effect.ron"),
+            include_str!("../../assets/effects/connection.effect.ron"),
+        ];
+
+        for source in sources {
+            let definition: EffectDefinition = ron::de::from_str(source).unwrap();
+            assert!(definition.validate().is_ok(), "effect '{}' failed validation", definition.name);
+        }
+    }
+
+    #[test]
+    fn test_undeclared_property_is_rejected() {
+        let definition = EffectDefinition {
+            name: "broken".into(),
+            capacity: 16,
+            spawner: SpawnerDefinition::Rate(1.0),
+            properties: Vec::new(),
+            init: vec![InitDefinition::SpawnFraction("missing".into())],
+            update: Vec::new(),
+            color_gradient: vec![(0.0, UniColor::white())],
+            theme_gradient: None,
+            size_gradient: Vec::new(),
+        };
+        assert!(definition.validate().is_err());
+    }
+}
@@ -1,54 +1,155 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use bevy_hanabi::prelude::*;
 
-/// Effects for magnetic field visualization
-#[derive(Resource)]
-pub struct MagneticEffects {
-    pub north: Handle<EffectAsset>,
-    pub south: Handle<EffectAsset>,
-    pub interaction: Handle<EffectAsset>,
+/// Every particle effect the application knows about. `Custom` kinds are
+/// handed out by [`EffectRegistry::register_custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum EffectKind {
+    NorthPole,
+    SouthPole,
+    Interaction,
+    NodeActive,
+    NodeHighlight,
+    NodePulse,
+    Collision,
+    Connection,
+    Fallback,
+    Custom(u32),
 }
 
-/// Effects for node visualization
-#[derive(Resource)]
-pub struct NodeEffects {
-    pub active: Handle<EffectAsset>,
-    pub highlight: Handle<EffectAsset>,
-    pub pulse: Handle<EffectAsset>,
+impl EffectKind {
+    /// The built-in kinds, each backed by a definition file
+    pub const BUILT_IN: [EffectKind; 9] = [
+        EffectKind::NorthPole,
+        EffectKind::SouthPole,
+        EffectKind::Interaction,
+        EffectKind::NodeActive,
+        EffectKind::NodeHighlight,
+        EffectKind::NodePulse,
+        EffectKind::Collision,
+        EffectKind::Connection,
+        EffectKind::Fallback,
+    ];
+
+    /// Definition file under `assets/` for built-in kinds
+    pub fn definition_path(&self) -> Option<&'static str> {
+        match self {
+            EffectKind::NorthPole => Some("effects/north_pole.effect.ron"),
+            EffectKind::SouthPole => Some("effects/south_pole.effect.ron"),
+            EffectKind::Interaction => Some("effects/interaction.effect.ron"),
+            EffectKind::NodeActive => Some("effects/node_active.effect.ron"),
+            EffectKind::NodeHighlight => Some("effects/node_highlight.effect.ron"),
+            EffectKind::NodePulse => Some("effects/node_pulse.effect.ron"),
+            EffectKind::Collision => Some("effects/collision.effect.ron"),
+            EffectKind::Connection => Some("effects/connection.effect.ron"),
+            EffectKind::Fallback => Some("effects/fallback.effect.ron"),
+            EffectKind::Custom(_) => None,
+        }
+    }
 }
 
-/// Effects for intersection visualization
-#[derive(Resource)]
-pub struct IntersectionEffects {
-    pub collision: Handle<EffectAsset>,
-    pub connection: Handle<EffectAsset>,
+/// The single owner of effect asset handles, keyed by [`EffectKind`]
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct EffectRegistry {
+    effects: HashMap<EffectKind, Handle<EffectAsset>>,
+    custom_names: HashMap<String, EffectKind>,
+    next_custom: u32,
 }
 
-impl Default for MagneticEffects {
-    fn default() -> Self {
-        Self {
-            north: Handle::default(),
-            south: Handle::default(),
-            interaction: Handle::default(),
-        }
+impl EffectRegistry {
+    pub fn insert(&mut self, kind: EffectKind, handle: Handle<EffectAsset>) {
+        self.effects.insert(kind, handle);
+    }
+
+    pub fn contains(&self, kind: EffectKind) -> bool {
+        self.effects.contains_key(&kind)
+    }
+
+    pub fn get(&self, kind: EffectKind) -> Option<&Handle<EffectAsset>> {
+        self.effects.get(&kind)
+    }
+
+    /// The handle for `kind`, reserving one for its definition to fill when
+    /// nothing is registered yet
+    pub fn get_or_reserve(&mut self, kind: EffectKind, assets: &Assets<EffectAsset>) -> Handle<EffectAsset> {
+        self.effects
+            .entry(kind)
+            .or_insert_with(|| assets.reserve_handle())
+            .clone()
+    }
+
+    /// The handle for `kind`, or the fallback effect when `kind` is not registered
+    pub fn get_or_fallback(&self, kind: EffectKind) -> Handle<EffectAsset> {
+        self.effects
+            .get(&kind)
+            .or_else(|| self.effects.get(&EffectKind::Fallback))
+            .cloned()
+            .unwrap_or_default()
     }
-}
 
-impl Default for NodeEffects {
-    fn default() -> Self {
-        Self {
-            active: Handle::default(),
-            highlight: Handle::default(),
-            pulse: Handle::default(),
+    /// Like [`get_or_fallback`](Self::get_or_fallback), but also falls back
+    /// when the registered asset has not been added or has been removed
+    pub fn resolve(&self, kind: EffectKind, assets: &Assets<EffectAsset>) -> Handle<EffectAsset> {
+        match self.effects.get(&kind) {
+            Some(handle) if assets.contains(handle) => handle.clone(),
+            _ => self.get_or_fallback(EffectKind::Fallback),
         }
     }
+
+    /// Register a user-defined effect under `name`. Registering the same name
+    /// again replaces the handle and keeps the kind.
+    pub fn register_custom(&mut self, name: impl Into<String>, handle: Handle<EffectAsset>) -> EffectKind {
+        let name = name.into();
+        let kind = match self.custom_names.get(&name) {
+            Some(kind) => *kind,
+            None => {
+                let kind = EffectKind::Custom(self.next_custom);
+                self.next_custom += 1;
+                self.custom_names.insert(name, kind);
+                kind
+            }
+        };
+        self.effects.insert(kind, handle);
+        kind
+    }
+
+    pub fn custom(&self, name: &str) -> Option<EffectKind> {
+        self.custom_names.get(name).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EffectKind, &Handle<EffectAsset>)> {
+        self.effects.iter().map(|(kind, handle)| (*kind, handle))
+    }
 }
 
-impl Default for IntersectionEffects {
-    fn default() -> Self {
-        Self {
-            collision: Handle::default(),
-            connection: Handle::default(),
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_lookup_and_fallback() {
+        let mut assets = Assets::<EffectAsset>::default();
+        let fallback = assets.add(EffectAsset::default());
+        let north = assets.reserve_handle();
+
+        let mut registry = EffectRegistry::default();
+        registry.insert(EffectKind::Fallback, fallback.clone());
+        registry.insert(EffectKind::NorthPole, north.clone());
+
+        assert_eq!(registry.get_or_fallback(EffectKind::NorthPole), north);
+        assert_eq!(registry.get_or_fallback(EffectKind::Collision), fallback);
+        // Reserved but never filled
+        assert_eq!(registry.resolve(EffectKind::NorthPole, &assets), fallback);
+        assert_eq!(registry.get_or_reserve(EffectKind::NorthPole, &assets), north);
+        let collision = registry.get_or_reserve(EffectKind::Collision, &assets);
+        assert_eq!(registry.get(EffectKind::Collision), Some(&collision));
+        assert!(!assets.contains(&collision));
+
+        let sparks = registry.register_custom("sparks", north.clone());
+        assert_eq!(registry.register_custom("sparks", fallback.clone()), sparks);
+        assert_eq!(registry.custom("sparks"), Some(sparks));
+        assert_eq!(registry.get(sparks), Some(&fallback));
+        assert_ne!(registry.register_custom("smoke", north), sparks);
     }
-} 
\ No newline at end of file
+}
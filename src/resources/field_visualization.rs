@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+use crate::{
+    err::{Result, ResourceError},
+    resources::colormap::ColormapPreset,
+};
+
+/// What the field visualization draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum FieldVisualizationMode {
+    #[default]
+    Off,
+    /// Arrow glyphs on a 3D grid through the sampled volume
+    Grid,
+    /// Arrow glyphs and a colour-mapped magnitude texture on one plane
+    Slice,
+}
+
+impl FieldVisualizationMode {
+    pub fn next(self) -> Self {
+        match self {
+            FieldVisualizationMode::Off => FieldVisualizationMode::Grid,
+            FieldVisualizationMode::Grid => FieldVisualizationMode::Slice,
+            FieldVisualizationMode::Slice => FieldVisualizationMode::Off,
+        }
+    }
+}
+
+/// Axis the slice plane is perpendicular to; the slice moves along it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum SliceAxis {
+    X,
+    #[default]
+    Y,
+    Z,
+}
+
+impl SliceAxis {
+    pub fn next(self) -> Self {
+        match self {
+            SliceAxis::X => SliceAxis::Y,
+            SliceAxis::Y => SliceAxis::Z,
+            SliceAxis::Z => SliceAxis::X,
+        }
+    }
+
+    /// Plane normal followed by the in-plane `u` and `v` axes
+    pub fn basis(self) -> (Vec3, Vec3, Vec3) {
+        match self {
+            SliceAxis::X => (Vec3::X, Vec3::Y, Vec3::Z),
+            SliceAxis::Y => (Vec3::Y, Vec3::X, Vec3::Z),
+            SliceAxis::Z => (Vec3::Z, Vec3::X, Vec3::Y),
+        }
+    }
+}
+
+/// Sampling of the combined node field for display
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct FieldVisualization {
+    pub mode: FieldVisualizationMode,
+    /// Centre of the sampled volume
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    /// Grid samples per axis
+    pub grid_resolution: u32,
+    /// Heat texture texels per side of the slice
+    pub slice_resolution: u32,
+    pub slice_axis: SliceAxis,
+    /// Slice position along `slice_axis`, relative to `center`
+    pub slice_offset: f32,
+    pub colormap: ColormapPreset,
+    /// Field magnitude mapped to the top of the colormap
+    pub max_magnitude: f32,
+    /// Map magnitudes logarithmically; dipole fields fall off too fast to read linearly
+    pub log_scale: bool,
+}
+
+impl Default for FieldVisualization {
+    fn default() -> Self {
+        Self {
+            mode: FieldVisualizationMode::Off,
+            center: Vec3::ZERO,
+            half_extents: Vec3::splat(5.0),
+            grid_resolution: 8,
+            slice_resolution: 24,
+            slice_axis: SliceAxis::Y,
+            slice_offset: 0.0,
+            colormap: ColormapPreset::Plasma,
+            max_magnitude: 10.0,
+            log_scale: true,
+        }
+    }
+}
+
+impl FieldVisualization {
+    pub fn validate(&self) -> Result<()> {
+        if self.half_extents.min_element() <= 0.0 {
+            return Err(ResourceError::InvalidConfig("Field visualization extents must be positive".to_string()).into());
+        }
+        if self.grid_resolution < 2 || self.slice_resolution < 2 {
+            return Err(ResourceError::InvalidConfig("Field visualization needs at least two samples per axis".to_string()).into());
+        }
+        if self.max_magnitude <= 0.0 || !self.max_magnitude.is_finite() {
+            return Err(ResourceError::InvalidConfig("Field visualization max magnitude must be positive".to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Half extent of the volume along the slice axis
+    pub fn slice_range(&self) -> f32 {
+        let (normal, _, _) = self.slice_axis.basis();
+        self.half_extents.dot(normal)
+    }
+
+    /// Move the slice, keeping it inside the volume
+    pub fn set_slice_offset(&mut self, offset: f32) {
+        let range = self.slice_range();
+        self.slice_offset = offset.clamp(-range, range);
+    }
+
+    /// Spacing between neighbouring grid samples, used to size arrow glyphs
+    pub fn grid_spacing(&self) -> f32 {
+        (self.half_extents * 2.0 / (self.grid_resolution - 1).max(1) as f32).min_element()
+    }
+
+    /// Half size of the slice plane along its `u` and `v` axes
+    pub fn slice_half_size(&self) -> Vec2 {
+        let (_, u, v) = self.slice_axis.basis();
+        Vec2::new(self.half_extents.dot(u), self.half_extents.dot(v))
+    }
+
+    /// Grid sample positions, corners included
+    pub fn grid_points(&self) -> Vec<Vec3> {
+        let n = self.grid_resolution.max(2);
+        let step = self.half_extents * 2.0 / (n - 1) as f32;
+        let min = self.center - self.half_extents;
+
+        let mut points = Vec::with_capacity((n * n * n) as usize);
+        for x in 0..n {
+            for y in 0..n {
+                for z in 0..n {
+                    points.push(min + step * Vec3::new(x as f32, y as f32, z as f32));
+                }
+            }
+        }
+        points
+    }
+
+    /// Centre of the slice plane in world space
+    pub fn slice_center(&self) -> Vec3 {
+        let (normal, _, _) = self.slice_axis.basis();
+        self.center + normal * self.slice_offset
+    }
+
+    /// `resolution`² slice sample positions at cell centres, row by row along `v`
+    pub fn slice_points(&self, resolution: u32) -> Vec<Vec3> {
+        let n = resolution.max(2);
+        let (_, u, v) = self.slice_axis.basis();
+        let half_size = self.slice_half_size();
+        let half_u = u * half_size.x;
+        let half_v = v * half_size.y;
+        let origin = self.slice_center() - half_u - half_v;
+
+        let mut points = Vec::with_capacity((n * n) as usize);
+        for j in 0..n {
+            for i in 0..n {
+                let s = (i as f32 + 0.5) / n as f32;
+                let t = (j as f32 + 0.5) / n as f32;
+                points.push(origin + half_u * 2.0 * s + half_v * 2.0 * t);
+            }
+        }
+        points
+    }
+
+    /// Position of `magnitude` along the colormap in `0.0..=1.0`
+    pub fn magnitude_fraction(&self, magnitude: f32) -> f32 {
+        let fraction = if self.log_scale {
+            magnitude.ln_1p() / self.max_magnitude.ln_1p()
+        } else {
+            magnitude / self.max_magnitude
+        };
+        fraction.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_sample_points() {
+        let mut vis = FieldVisualization {
+            half_extents: Vec3::new(1.0, 2.0, 3.0),
+            grid_resolution: 3,
+            slice_axis: SliceAxis::Z,
+            ..default()
+        };
+
+        let grid = vis.grid_points();
+        assert_eq!(grid.len(), 27);
+        assert_eq!(grid[0], Vec3::new(-1.0, -2.0, -3.0));
+        assert_eq!(grid[26], Vec3::new(1.0, 2.0, 3.0));
+
+        vis.set_slice_offset(10.0);
+        assert_eq!(vis.slice_offset, 3.0);
+        let slice = vis.slice_points(2);
+        assert_eq!(slice.len(), 4);
+        assert!(slice.iter().all(|point| point.z == 3.0));
+        assert_eq!(slice[0], Vec3::new(-0.5, -1.0, 3.0));
+        assert_eq!(slice[1], Vec3::new(0.5, -1.0, 3.0));
+
+        assert_eq!(vis.magnitude_fraction(0.0), 0.0);
+        assert_eq!(vis.magnitude_fraction(vis.max_magnitude * 2.0), 1.0);
+    }
+}
@@ -1,7 +1,13 @@
 use bevy::prelude::*;
 use crate::err::{Result, ResourceError};
 
-#[derive(Resource)]
+/// Ask for every node and connection to be replaced by a fresh helix built
+/// from the current `HelixConfig`
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct RegenerateHelix;
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
 pub struct HelixConfig {
     pub radius: f32,
     pub vertical_step: f32,
@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+use bevy::prelude::*;
+use crate::{
+    components::{Direction, MagneticField, Node},
+    err::{Result, ResourceError},
+};
+
+/// One node as it was when a snapshot was taken
+#[derive(Debug, Clone, Reflect)]
+pub struct NodeSnapshot {
+    pub node: Node,
+    pub field: MagneticField,
+    pub transform: Transform,
+}
+
+/// A connection between two nodes, by index into `SimulationSnapshot::nodes`
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct ConnectionSnapshot {
+    pub start: usize,
+    pub end: usize,
+    pub direction: Direction,
+}
+
+/// The full simulation state at one tick
+#[derive(Debug, Clone, Reflect)]
+pub struct SimulationSnapshot {
+    /// Simulation ticks since startup
+    pub tick: u64,
+    /// Virtual time in seconds
+    pub elapsed: f32,
+    pub nodes: Vec<NodeSnapshot>,
+    pub connections: Vec<ConnectionSnapshot>,
+}
+
+/// Ask for the simulation to be rewound to the snapshot at this index
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RestoreSnapshot(pub usize);
+
+/// Bounded ring buffer of snapshots taken every `interval` ticks; the oldest
+/// snapshot is dropped once `capacity` is reached.
+#[derive(Resource, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct SimulationHistory {
+    pub capacity: usize,
+    /// Ticks between snapshots
+    pub interval: u64,
+    /// Ticks the simulation has advanced
+    pub tick: u64,
+    /// Snapshot currently restored; recording resumes after it, discarding
+    /// the later snapshots, once the simulation advances again
+    pub cursor: Option<usize>,
+    snapshots: VecDeque<SimulationSnapshot>,
+}
+
+impl Default for SimulationHistory {
+    fn default() -> Self {
+        Self {
+            capacity: 600,
+            interval: 10,
+            tick: 0,
+            cursor: None,
+            snapshots: VecDeque::new(),
+        }
+    }
+}
+
+impl SimulationHistory {
+    pub fn validate(&self) -> Result<()> {
+        if self.capacity == 0 {
+            return Err(ResourceError::InvalidConfig("History capacity must be at least one snapshot".to_string()).into());
+        }
+        if self.interval == 0 {
+            return Err(ResourceError::InvalidConfig("History interval must be at least one tick".to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Whether a snapshot is due at the current tick
+    pub fn due(&self) -> bool {
+        self.tick % self.interval.max(1) == 0
+    }
+
+    pub fn push(&mut self, snapshot: SimulationSnapshot) {
+        // Resuming from a restored point forks the timeline
+        if let Some(cursor) = self.cursor.take() {
+            self.snapshots.truncate(cursor + 1);
+        }
+        while self.snapshots.len() >= self.capacity.max(1) {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Show snapshot `index` and count ticks on from it, so snapshots taken
+    /// after resuming keep their interval and tick numbers
+    pub fn rewind_to(&mut self, index: usize) -> Option<&SimulationSnapshot> {
+        self.tick = self.snapshots.get(index)?.tick;
+        self.cursor = Some(index);
+        self.snapshots.get(index)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&SimulationSnapshot> {
+        self.snapshots.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Snapshot index nearest to `fraction` (0..=1) along the timeline
+    pub fn index_at(&self, fraction: f32) -> Option<usize> {
+        let last = self.snapshots.len().checked_sub(1)?;
+        Some((fraction.clamp(0.0, 1.0) * last as f32).round() as usize)
+    }
+
+    /// Position of `index` along the timeline as a fraction
+    pub fn fraction_of(&self, index: usize) -> f32 {
+        match self.snapshots.len() {
+            0 | 1 => 1.0,
+            len => index.min(len - 1) as f32 / (len - 1) as f32,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.cursor = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(tick: u64) -> SimulationSnapshot {
+        SimulationSnapshot {
+            tick,
+            elapsed: tick as f32 / 60.0,
+            nodes: Vec::new(),
+            connections: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_forks_on_resume() {
+        let mut history = SimulationHistory {
+            capacity: 3,
+            ..default()
+        };
+        assert!(history.validate().is_ok());
+        assert_eq!(history.index_at(0.5), None);
+
+        for tick in 0..5 {
+            history.push(snapshot(tick));
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(0).unwrap().tick, 2);
+        assert_eq!(history.index_at(0.5), Some(1));
+        assert_eq!(history.fraction_of(2), 1.0);
+
+        // Restoring the oldest snapshot resumes counting from its tick, and
+        // advancing drops the later ones
+        history.tick = 40;
+        assert_eq!(history.rewind_to(0).map(|restored| restored.tick), Some(2));
+        assert_eq!(history.tick, 2);
+        history.tick += 1;
+        history.push(snapshot(history.tick));
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(1).unwrap().tick, 3);
+        assert!(history.cursor.is_none());
+        assert_eq!(history.rewind_to(5).map(|restored| restored.tick), None);
+    }
+}
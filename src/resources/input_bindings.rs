@@ -0,0 +1,364 @@
+use bevy::{
+    prelude::*,
+    asset::{io::Reader, AssetLoader, LoadContext},
+    input::gamepad::{GamepadAxis, GamepadButton},
+    utils::HashMap,
+};
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::err::{Error, Result, ResourceError};
+
+/// User binding overrides, hot reloaded
+pub const INPUT_BINDINGS_PATH: &str = "input/bindings.ron";
+
+/// Everything the user can trigger from a keyboard, mouse or gamepad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum HyvoAction {
+    // Simulation
+    Pause,
+    SpeedUp,
+    SpeedDown,
+    /// Advance one physics tick while paused
+    Step,
+    /// Regenerate the helix from `HelixConfig`
+    Reset,
+
+    // Camera; rates are per second, drags are per pixel
+    Orbit,
+    Pan,
+    Zoom,
+    OrbitDrag,
+    PanDrag,
+    OrbitJumpLeft,
+    OrbitJumpRight,
+    OrbitJumpUp,
+    OrbitJumpDown,
+    FrameAll,
+    FrameSelection,
+    /// Fly to bookmark slot 1–9
+    Bookmark(u8),
+    SaveBookmark(u8),
+    ToggleCameraPath,
+    StopCameraPath,
+
+    // Selection and editing
+    Select,
+    /// Held to add to the selection instead of replacing it
+    ExtendSelection,
+    SpawnNode,
+    DeleteSelected,
+    FlipPolarity,
+    CycleSpawnShape,
+    CycleSpawnPolarity,
+
+    // Overlays
+    ToggleTrails,
+    CycleTrailColors,
+    CycleFieldView,
+    CycleSliceAxis,
+    ToggleSplitView,
+    CycleTheme,
+    ToggleTimeline,
+    ToggleHud,
+    ToggleInspector,
+
+    Screenshot,
+    Exit,
+}
+
+impl Actionlike for HyvoAction {
+    fn input_control_kind(&self) -> InputControlKind {
+        match self {
+            HyvoAction::Orbit | HyvoAction::Pan | HyvoAction::OrbitDrag | HyvoAction::PanDrag => {
+                InputControlKind::DualAxis
+            }
+            HyvoAction::Zoom => InputControlKind::Axis,
+            _ => InputControlKind::Button,
+        }
+    }
+}
+
+/// Gamepad thumbsticks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+/// One input bound to an action, as written in the bindings file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    /// A key while either side's modifier is held, e.g. `Modified(Shift, KeyL)`
+    Modified(ModifierKey, KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    /// Two keys as an axis
+    KeyAxis { negative: KeyCode, positive: KeyCode },
+    /// Two gamepad buttons as an axis, e.g. the triggers
+    GamepadButtonAxis { negative: GamepadButton, positive: GamepadButton },
+    GamepadAxis(GamepadAxis),
+    /// Four keys as a direction, optionally only while a modifier is held
+    KeyPad {
+        up: KeyCode,
+        down: KeyCode,
+        left: KeyCode,
+        right: KeyCode,
+        #[serde(default)]
+        modifier: Option<ModifierKey>,
+    },
+    /// Mouse motion while a button, and optionally a modifier, is held
+    MouseDrag {
+        button: MouseButton,
+        #[serde(default)]
+        modifier: Option<ModifierKey>,
+    },
+    Stick(Stick),
+}
+
+impl Binding {
+    /// The kind of action this binding can drive
+    pub fn kind(&self) -> InputControlKind {
+        match self {
+            Binding::Key(_) | Binding::Modified(..) | Binding::Mouse(_) | Binding::Gamepad(_) => {
+                InputControlKind::Button
+            }
+            Binding::KeyAxis { .. } | Binding::GamepadButtonAxis { .. } | Binding::GamepadAxis(_) => {
+                InputControlKind::Axis
+            }
+            Binding::KeyPad { .. } | Binding::MouseDrag { .. } | Binding::Stick(_) => InputControlKind::DualAxis,
+        }
+    }
+
+    fn insert_into(&self, map: &mut InputMap<HyvoAction>, action: HyvoAction) {
+        match self.clone() {
+            Binding::Key(key) => {
+                map.insert(action, key);
+            }
+            Binding::Modified(modifier, key) => {
+                map.insert(action, ButtonlikeChord::modified(modifier, key));
+            }
+            Binding::Mouse(button) => {
+                map.insert(action, button);
+            }
+            Binding::Gamepad(button) => {
+                map.insert(action, button);
+            }
+            Binding::KeyAxis { negative, positive } => {
+                map.insert_axis(action, VirtualAxis::new(negative, positive));
+            }
+            Binding::GamepadButtonAxis { negative, positive } => {
+                map.insert_axis(action, VirtualAxis::new(negative, positive));
+            }
+            Binding::GamepadAxis(axis) => {
+                map.insert_axis(action, GamepadControlAxis::new(axis));
+            }
+            Binding::KeyPad { up, down, left, right, modifier: None } => {
+                map.insert_dual_axis(action, VirtualDPad::new(up, down, left, right));
+            }
+            Binding::KeyPad { up, down, left, right, modifier: Some(modifier) } => {
+                let chord = |key: KeyCode| ButtonlikeChord::modified(modifier, key);
+                map.insert_dual_axis(action, VirtualDPad::new(chord(up), chord(down), chord(left), chord(right)));
+            }
+            Binding::MouseDrag { button, modifier: None } => {
+                map.insert_dual_axis(action, DualAxislikeChord::new(button, MouseMove::default()));
+            }
+            Binding::MouseDrag { button, modifier: Some(modifier) } => {
+                map.insert_dual_axis(
+                    action,
+                    DualAxislikeChord::new(ButtonlikeChord::modified(modifier, button), MouseMove::default()),
+                );
+            }
+            Binding::Stick(Stick::Left) => {
+                map.insert_dual_axis(action, GamepadStick::LEFT);
+            }
+            Binding::Stick(Stick::Right) => {
+                map.insert_dual_axis(action, GamepadStick::RIGHT);
+            }
+        }
+    }
+}
+
+const DIGIT_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Built-in bindings for keyboard, mouse and gamepad
+pub fn default_bindings() -> HashMap<HyvoAction, Vec<Binding>> {
+    use Binding::*;
+    use HyvoAction as A;
+
+    let arrows = |modifier| KeyPad {
+        up: KeyCode::ArrowUp,
+        down: KeyCode::ArrowDown,
+        left: KeyCode::ArrowLeft,
+        right: KeyCode::ArrowRight,
+        modifier,
+    };
+
+    let mut bindings: HashMap<HyvoAction, Vec<Binding>> = [
+        (A::Pause, vec![Key(KeyCode::Space), Gamepad(GamepadButton::Start)]),
+        // Alt keeps the speed arrows apart from the orbit pad, like Ctrl for jumps
+        (A::SpeedUp, vec![Modified(ModifierKey::Alt, KeyCode::ArrowUp), Gamepad(GamepadButton::DPadUp)]),
+        (A::SpeedDown, vec![Modified(ModifierKey::Alt, KeyCode::ArrowDown), Gamepad(GamepadButton::DPadDown)]),
+        (A::Step, vec![Key(KeyCode::KeyS), Gamepad(GamepadButton::DPadRight)]),
+        (A::Reset, vec![Key(KeyCode::KeyR), Gamepad(GamepadButton::Select)]),
+        (A::Orbit, vec![arrows(None), Binding::Stick(self::Stick::Right)]),
+        (A::Pan, vec![arrows(Some(ModifierKey::Shift)), Binding::Stick(self::Stick::Left)]),
+        (
+            A::Zoom,
+            vec![
+                KeyAxis { negative: KeyCode::KeyZ, positive: KeyCode::KeyX },
+                GamepadButtonAxis { negative: GamepadButton::RightTrigger2, positive: GamepadButton::LeftTrigger2 },
+            ],
+        ),
+        (A::OrbitDrag, vec![MouseDrag { button: MouseButton::Middle, modifier: None }]),
+        (A::PanDrag, vec![MouseDrag { button: MouseButton::Middle, modifier: Some(ModifierKey::Shift) }]),
+        (A::OrbitJumpLeft, vec![Modified(ModifierKey::Control, KeyCode::ArrowLeft)]),
+        (A::OrbitJumpRight, vec![Modified(ModifierKey::Control, KeyCode::ArrowRight)]),
+        (A::OrbitJumpUp, vec![Modified(ModifierKey::Control, KeyCode::ArrowUp)]),
+        (A::OrbitJumpDown, vec![Modified(ModifierKey::Control, KeyCode::ArrowDown)]),
+        (A::FrameAll, vec![Key(KeyCode::Home), Gamepad(GamepadButton::North)]),
+        (A::FrameSelection, vec![Key(KeyCode::Period)]),
+        (A::ToggleCameraPath, vec![Key(KeyCode::KeyC), Gamepad(GamepadButton::West)]),
+        (A::StopCameraPath, vec![Modified(ModifierKey::Shift, KeyCode::KeyC)]),
+        (A::Select, vec![Mouse(MouseButton::Left)]),
+        (A::ExtendSelection, vec![Key(KeyCode::ShiftLeft), Key(KeyCode::ShiftRight)]),
+        (A::SpawnNode, vec![Key(KeyCode::KeyN)]),
+        (A::DeleteSelected, vec![Key(KeyCode::Delete), Key(KeyCode::Backspace)]),
+        (A::FlipPolarity, vec![Key(KeyCode::KeyP)]),
+        (A::CycleSpawnShape, vec![Key(KeyCode::Tab)]),
+        (A::CycleSpawnPolarity, vec![Key(KeyCode::KeyM)]),
+        (A::ToggleTrails, vec![Key(KeyCode::KeyL)]),
+        (A::CycleTrailColors, vec![Modified(ModifierKey::Shift, KeyCode::KeyL)]),
+        (A::CycleFieldView, vec![Key(KeyCode::KeyF), Gamepad(GamepadButton::East)]),
+        (A::CycleSliceAxis, vec![Modified(ModifierKey::Shift, KeyCode::KeyF)]),
+        (A::ToggleSplitView, vec![Key(KeyCode::KeyV)]),
+        (A::CycleTheme, vec![Key(KeyCode::KeyT)]),
+        (A::ToggleTimeline, vec![Key(KeyCode::KeyH)]),
+        (A::ToggleHud, vec![Key(KeyCode::F1)]),
+        (A::ToggleInspector, vec![Key(KeyCode::F2)]),
+        (A::Screenshot, vec![Key(KeyCode::F12)]),
+        (A::Exit, vec![Key(KeyCode::Escape)]),
+    ]
+    .into_iter()
+    .collect();
+
+    for (index, key) in DIGIT_KEYS.into_iter().enumerate() {
+        let slot = index as u8 + 1;
+        bindings.insert(A::Bookmark(slot), vec![Key(key)]);
+        bindings.insert(A::SaveBookmark(slot), vec![Modified(ModifierKey::Control, key)]);
+    }
+    bindings
+}
+
+/// Binding overrides loaded from `input/bindings.ron`. Each listed action
+/// replaces all of its default bindings; an empty list unbinds it.
+#[derive(Asset, TypePath, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputBindings {
+    #[serde(default)]
+    pub bindings: HashMap<HyvoAction, Vec<Binding>>,
+}
+
+impl InputBindings {
+    pub fn validate(&self) -> Result<()> {
+        for (action, bindings) in &self.bindings {
+            if let HyvoAction::Bookmark(slot) | HyvoAction::SaveBookmark(slot) = action {
+                if !(1..=9).contains(slot) {
+                    return Err(ResourceError::InvalidConfig(format!("{action:?}: bookmark slots are 1–9")).into());
+                }
+            }
+            if let Some(binding) = bindings.iter().find(|binding| binding.kind() != action.input_control_kind()) {
+                return Err(ResourceError::InvalidConfig(format!(
+                    "{action:?} is a {:?} action but {binding:?} is a {:?} input",
+                    action.input_control_kind(),
+                    binding.kind()
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Defaults with these overrides applied
+    pub fn input_map(&self) -> InputMap<HyvoAction> {
+        let mut bindings = default_bindings();
+        bindings.extend(self.bindings.iter().map(|(action, list)| (*action, list.clone())));
+
+        let mut map = InputMap::default();
+        for (action, list) in &bindings {
+            for binding in list {
+                binding.insert_into(&mut map, *action);
+            }
+        }
+        map
+    }
+}
+
+/// Loads `.bindings.ron` files into [`InputBindings`] assets
+#[derive(Default)]
+pub struct InputBindingsLoader;
+
+impl AssetLoader for InputBindingsLoader {
+    type Asset = InputBindings;
+    type Settings = ();
+    type Error = Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> std::result::Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(Error::from_io)?;
+        let bindings: InputBindings = ron::de::from_bytes(&bytes)
+            .map_err(|e| ResourceError::LoadFailed(format!("Invalid input bindings RON: {e}")))?;
+
+        bindings.validate()?;
+        Ok(bindings)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bindings.ron"]
+    }
+}
+
+/// Handle to the user's binding overrides
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct InputBindingsHandle(pub Handle<InputBindings>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_match_action_kinds() {
+        let defaults = InputBindings { bindings: default_bindings() };
+        assert!(defaults.validate().is_ok());
+        assert!(defaults.bindings.contains_key(&HyvoAction::SaveBookmark(9)));
+
+        let overrides: InputBindings = ron::de::from_str(
+            "(bindings: { Pause: [Key(KeyK)], Zoom: [KeyAxis(negative: KeyQ, positive: KeyE)], Bookmark(2): [] })",
+        )
+        .unwrap();
+        assert!(overrides.validate().is_ok());
+
+        let wrong_kind = InputBindings {
+            bindings: [(HyvoAction::Orbit, vec![Binding::Key(KeyCode::KeyO)])].into_iter().collect(),
+        };
+        assert!(wrong_kind.validate().is_err());
+
+        let shipped: InputBindings = ron::de::from_str(include_str!("../../assets/input/bindings.ron")).unwrap();
+        assert!(shipped.validate().is_ok());
+    }
+}
@@ -4,10 +4,11 @@ use bevy::{
 };
 use crate::{
     err::{Error, ErrorManager, ResourceError},
-    resources::uni_color::UniColor,
+    resources::uni_color::{MaterialColors, UniColor},
 };
 
-#[derive(Resource)]
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
 pub struct MaterialHandles {
     pub node_material: Handle<StandardMaterial>,
     pub connection_material: Handle<StandardMaterial>,
@@ -24,47 +25,66 @@ impl Default for MaterialHandles {
     }
 }
 
-pub fn create_materials(
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut material_handles: ResMut<MaterialHandles>,
-    mut error_manager: ResMut<ErrorManager>,
-) {
-    // Create default material
-    let base_color = UniColor::srgb(0.9, 0.9, 0.9);
-    let emissive = UniColor::srgb(0.1, 0.1, 0.1);
-    
-    let material = StandardMaterial {
-        base_color: base_color.as_bevy_color(),
-        emissive: emissive.as_linear_rgba(),
-        ..default()
-    };
+impl MaterialHandles {
+    /// Build the standard materials from a colour set. Handles that already
+    /// exist are updated in place so entities using them follow theme changes.
+    pub fn apply_colors(
+        &mut self,
+        colors: &MaterialColors,
+        materials: &mut Assets<StandardMaterial>,
+    ) {
+        let node_material = StandardMaterial {
+            base_color: colors.node.as_bevy_color(),
+            emissive: colors.emissive.as_linear_rgba(),
+            ..default()
+        };
 
-    material_handles.node_material = materials.add(material);
+        let connection_material = StandardMaterial {
+            base_color: colors.connection.as_bevy_color(),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        };
 
-    // Create connection material
-    let connection_color = UniColor::srgb(0.3, 0.5, 0.8).with_alpha(0.8);
-    let connection_material = StandardMaterial {
-        base_color: connection_color.as_bevy_color(),
-        alpha_mode: AlphaMode::Blend,
-        ..default()
-    };
+        let highlight_material = StandardMaterial {
+            base_color: colors.highlight.as_bevy_color(),
+            emissive: colors.highlight.lerp(UniColor::black(), 0.2).as_linear_rgba(),
+            ..default()
+        };
 
-    material_handles.connection_material = materials.add(connection_material);
+        Self::upsert(&mut self.node_material, node_material, materials);
+        Self::upsert(&mut self.connection_material, connection_material, materials);
+        Self::upsert(&mut self.highlight_material, highlight_material, materials);
+    }
 
-    // Create highlight material
-    let highlight_color = UniColor::srgb(1.0, 0.8, 0.0);
-    let highlight_emissive = UniColor::srgb(0.8, 0.6, 0.0);
-    
-    let highlight_material = StandardMaterial {
-        base_color: highlight_color.as_bevy_color(),
-        emissive: highlight_emissive.as_linear_rgba(),
-        ..default()
-    };
+    fn upsert(
+        handle: &mut Handle<StandardMaterial>,
+        material: StandardMaterial,
+        materials: &mut Assets<StandardMaterial>,
+    ) {
+        if *handle != Handle::default() && materials.contains(handle.id()) {
+            materials.insert(handle.id(), material);
+        } else {
+            *handle = materials.add(material);
+        }
+    }
+}
 
-    material_handles.highlight_material = materials.add(highlight_material);
+pub fn create_materials(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut material_handles: ResMut<MaterialHandles>,
+    colors: Option<Res<MaterialColors>>,
+    error_manager: Res<ErrorManager>,
+) {
+    let colors = match colors {
+        Some(colors) => colors.clone(),
+        None => {
+            error_manager.report_with_recovery(
+                Error::Resource(ResourceError::NotFound("MaterialColors".into())),
+                "Using default material colours",
+            );
+            MaterialColors::default()
+        }
+    };
 
-    error_manager.report_with_recovery(
-        Error::Resource(ResourceError::LoadFailed("Materials initialized".into())),
-        "Using default materials"
-    );
-} 
\ No newline at end of file
+    material_handles.apply_colors(&colors, &mut materials);
+}
@@ -1,47 +1,52 @@
+pub mod camera_bookmarks;
+pub mod camera_path;
+pub mod colormap;
 mod config;
+pub mod effect_definition;
 mod effects;
+pub mod field_visualization;
 mod helix_config;
+pub mod history;
+pub mod input_bindings;
 mod materials;
+pub mod particle_budget;
+pub mod selection;
+pub mod theme;
 pub mod uni_color;
-
-use bevy::prelude::*;
-use bevy_hanabi::EffectAsset;
+pub mod viewports;
 
 pub use config::{
     AnimationState,
     CameraState,
     HyvoGridConfig,
+    LorentzConfig,
+    NodeSpawnSettings,
+    ParticleBackend,
     SimulationConfig,
+    TrailColorMode,
+    TrailSettings,
 };
 
-pub use effects::{
-    MagneticEffects,
-    NodeEffects,
-    IntersectionEffects,
-};
+pub use effects::{EffectKind, EffectRegistry};
 
-pub use helix_config::HelixConfig;
+pub use camera_bookmarks::{CameraBookmark, CameraBookmarks};
+pub use camera_path::{CameraPath, CameraPathLoader, CameraPathPlayer, PathClock};
+pub use colormap::{Colormap, ColormapPreset};
+pub use effect_definition::{EffectDefinition, EffectDefinitionLoader, EffectLibrary};
+pub use field_visualization::{FieldVisualization, FieldVisualizationMode, SliceAxis};
+pub use helix_config::{HelixConfig, RegenerateHelix};
+pub use history::{RestoreSnapshot, SimulationHistory, SimulationSnapshot};
+pub use input_bindings::{HyvoAction, InputBindings, InputBindingsHandle, InputBindingsLoader};
 pub use materials::{MaterialConfig, Materials, MaterialHandles};
+pub use particle_budget::{BudgetCulled, ParticleBudget};
+pub use selection::{Selection, SelectionChanged};
+pub use theme::{Theme, ThemeLoader, ThemeSettings};
 pub use uni_color::{UniColor, MaterialColors};
+pub use viewports::{OrthoAxis, ViewportLayout};
 
 // Re-export common types
 pub use bevy::pbr::StandardMaterial;
 
-#[derive(Resource)]
-pub struct Effects {
-    pub collision: Handle<EffectAsset>,
-    pub connection: Handle<EffectAsset>,
-}
-
-impl Default for Effects {
-    fn default() -> Self {
-        Self {
-            collision: Handle::default(),
-            connection: Handle::default(),
-        }
-    }
-}
-
 // Add color conversion helpers
 pub mod color_helpers {
     use super::uni_color::UniColor;
@@ -0,0 +1,152 @@
+use bevy::{prelude::*, utils::HashSet};
+use crate::err::{Result, ResourceError};
+
+/// Marks an emitter or effect hidden by the budget rather than by the user
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct BudgetCulled;
+
+/// Global particle budget. Configuration is public; usage fields are written
+/// each frame by `systems::budget::update_particle_budget` for the HUD.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct ParticleBudget {
+    /// Total particle capacity allowed across all active emitters
+    pub max_particles: u32,
+    /// Emitters beyond this distance from the camera may be culled when over budget
+    pub cull_distance: f32,
+    /// Spawn rates are never scaled below this fraction
+    pub min_spawn_scale: f32,
+    /// Capacity of every emitter, including culled ones
+    pub requested: u32,
+    /// Capacity of emitters still running after culling
+    pub active_capacity: u32,
+    /// Live CPU particles; GPU particle counts are not read back
+    pub cpu_live: usize,
+    pub emitters: usize,
+    pub culled: usize,
+    /// Multiplier consumers apply to their spawn rates
+    pub spawn_scale: f32,
+}
+
+impl Default for ParticleBudget {
+    fn default() -> Self {
+        Self {
+            max_particles: 10_000,
+            cull_distance: 30.0,
+            min_spawn_scale: 0.1,
+            requested: 0,
+            active_capacity: 0,
+            cpu_live: 0,
+            emitters: 0,
+            culled: 0,
+            spawn_scale: 1.0,
+        }
+    }
+}
+
+/// One emitter as seen by the budget
+#[derive(Debug, Clone, Copy)]
+pub struct BudgetEntry {
+    pub entity: Entity,
+    pub capacity: u32,
+    pub distance: f32,
+}
+
+/// Which emitters to cull and how far to scale the rest
+#[derive(Debug, Default)]
+pub struct BudgetPlan {
+    pub culled: HashSet<Entity>,
+    pub active_capacity: u32,
+    pub spawn_scale: f32,
+}
+
+impl ParticleBudget {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_particles == 0 {
+            return Err(ResourceError::InvalidConfig("Particle budget must be positive".to_string()).into());
+        }
+        if self.cull_distance < 0.0 {
+            return Err(ResourceError::InvalidConfig("Cull distance cannot be negative".to_string()).into());
+        }
+        if !(0.0..=1.0).contains(&self.min_spawn_scale) {
+            return Err(ResourceError::InvalidConfig("Minimum spawn scale must be within 0.0..=1.0".to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Fraction of the budget taken by active emitters
+    pub fn usage(&self) -> f32 {
+        self.active_capacity as f32 / self.max_particles.max(1) as f32
+    }
+
+    pub fn over_budget(&self) -> bool {
+        self.requested > self.max_particles
+    }
+
+    /// Cull the furthest emitters beyond `cull_distance` until under budget,
+    /// then scale spawn rates to cover whatever overshoot remains
+    pub fn plan(&self, entries: &[BudgetEntry]) -> BudgetPlan {
+        let mut active_capacity: u32 = entries.iter().map(|entry| entry.capacity).sum();
+        let mut culled = HashSet::new();
+
+        if active_capacity > self.max_particles {
+            let mut distant: Vec<&BudgetEntry> = entries
+                .iter()
+                .filter(|entry| entry.distance > self.cull_distance)
+                .collect();
+            distant.sort_by(|a, b| b.distance.total_cmp(&a.distance));
+
+            for entry in distant {
+                if active_capacity <= self.max_particles {
+                    break;
+                }
+                culled.insert(entry.entity);
+                active_capacity -= entry.capacity;
+            }
+        }
+
+        let spawn_scale = if active_capacity > self.max_particles {
+            (self.max_particles as f32 / active_capacity as f32).max(self.min_spawn_scale)
+        } else {
+            1.0
+        };
+
+        BudgetPlan {
+            culled,
+            active_capacity,
+            spawn_scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32, capacity: u32, distance: f32) -> BudgetEntry {
+        BudgetEntry { entity: Entity::from_raw(id), capacity, distance }
+    }
+
+    #[test]
+    fn test_budget_culls_furthest_then_scales() {
+        let budget = ParticleBudget {
+            max_particles: 1000,
+            cull_distance: 10.0,
+            ..default()
+        };
+
+        let under = budget.plan(&[entry(1, 400, 50.0), entry(2, 400, 5.0)]);
+        assert!(under.culled.is_empty());
+        assert_eq!(under.spawn_scale, 1.0);
+
+        let over = budget.plan(&[entry(1, 600, 50.0), entry(2, 600, 8.0), entry(3, 600, 5.0)]);
+        assert!(over.culled.contains(&Entity::from_raw(1)));
+        assert_eq!(over.active_capacity, 1200);
+        assert!((over.spawn_scale - 1000.0 / 1200.0).abs() < 1e-6);
+
+        let nearby = budget.plan(&[entry(1, 5000, 1.0), entry(2, 5000, 2.0)]);
+        assert!(nearby.culled.is_empty());
+        assert_eq!(nearby.spawn_scale, 0.1);
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+/// The set of selected nodes, in selection order. The most recently
+/// selected entity is the primary selection.
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct Selection {
+    entities: Vec<Entity>,
+}
+
+/// Sent whenever the selection changes
+#[derive(Event, Debug, Clone, Default)]
+pub struct SelectionChanged {
+    pub added: Vec<Entity>,
+    pub removed: Vec<Entity>,
+}
+
+impl Selection {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    pub fn primary(&self) -> Option<Entity> {
+        self.entities.last().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().copied()
+    }
+
+    /// Replace the selection with a single entity
+    pub fn select(&mut self, entity: Entity) {
+        self.entities.clear();
+        self.entities.push(entity);
+    }
+
+    /// Add an entity, making it the primary selection
+    pub fn add(&mut self, entity: Entity) {
+        self.entities.retain(|e| *e != entity);
+        self.entities.push(entity);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        self.entities.retain(|e| *e != entity);
+    }
+
+    /// Add the entity if absent, otherwise remove it
+    pub fn toggle(&mut self, entity: Entity) {
+        if self.contains(entity) {
+            self.remove(entity);
+        } else {
+            self.add(entity);
+        }
+    }
+
+    /// Replace the selection with the given entities
+    pub fn set(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        self.entities.clear();
+        self.extend(entities);
+    }
+
+    pub fn extend(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        for entity in entities {
+            self.add(entity);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&Entity) -> bool) {
+        self.entities.retain(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_operations() {
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let mut selection = Selection::default();
+
+        selection.select(a);
+        assert_eq!(selection.primary(), Some(a));
+
+        selection.toggle(b);
+        assert_eq!(selection.len(), 2);
+        assert_eq!(selection.primary(), Some(b));
+
+        selection.toggle(a);
+        assert!(!selection.contains(a));
+
+        selection.set([a, b, a]);
+        assert_eq!(selection.len(), 2);
+        assert_eq!(selection.primary(), Some(a));
+
+        selection.clear();
+        assert!(selection.is_empty());
+    }
+}
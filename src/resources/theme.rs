@@ -0,0 +1,199 @@
+use bevy::{
+    prelude::*,
+    asset::{io::Reader, AssetLoader, LoadContext},
+};
+use bevy_hanabi::Gradient;
+use serde::{Deserialize, Serialize};
+use crate::{
+    err::{Error, Result, ResourceError},
+    resources::{
+        colormap::Colormap,
+        uni_color::{MaterialColors, UniColor},
+    },
+};
+
+/// Theme files shipped in `assets/themes`, cycled in this order
+pub const THEME_PATHS: [&str; 3] = [
+    "themes/dark.theme.ron",
+    "themes/light.theme.ron",
+    "themes/presentation.theme.toml",
+];
+
+/// A complete colour theme, loaded from RON or TOML and hot reloaded.
+///
+/// `MaterialHandles`, `MaterialColors`, the clear colour and the polarity
+/// effect gradients are all derived from the active theme.
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub node: UniColor,
+    pub connection: UniColor,
+    pub highlight: UniColor,
+    pub background: UniColor,
+    pub emissive: UniColor,
+    pub north: UniColor,
+    pub south: UniColor,
+    pub particle_gradient: Vec<(f32, UniColor)>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let colors = MaterialColors::default();
+        Self {
+            name: "dark".to_string(),
+            node: colors.node,
+            connection: colors.connection,
+            highlight: colors.highlight,
+            background: colors.background,
+            emissive: colors.emissive,
+            north: colors.north,
+            south: colors.south,
+            particle_gradient: crate::resources::uni_color::presets::particle_gradient(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn validate(&self) -> Result<()> {
+        let colors = [
+            self.node,
+            self.connection,
+            self.highlight,
+            self.background,
+            self.emissive,
+            self.north,
+            self.south,
+        ];
+        if colors.iter().any(|color| !color.is_valid()) {
+            return Err(ResourceError::ValidationFailed(format!("Theme '{}' contains an invalid colour", self.name)).into());
+        }
+        self.particle_colormap().map(|_| ())
+    }
+
+    pub fn material_colors(&self) -> MaterialColors {
+        MaterialColors {
+            node: self.node,
+            connection: self.connection,
+            highlight: self.highlight,
+            background: self.background,
+            emissive: self.emissive,
+            north: self.north,
+            south: self.south,
+        }
+    }
+
+    pub fn particle_colormap(&self) -> Result<Colormap> {
+        Colormap::custom(self.particle_gradient.clone())
+    }
+
+    /// Hanabi gradient for the general particle effects
+    pub fn particle_effect_gradient(&self) -> Gradient<Vec4> {
+        self.particle_colormap()
+            .unwrap_or_default()
+            .to_gradient()
+    }
+
+    /// Hanabi gradient for north or south pole particles, fading out over lifetime
+    pub fn polarity_gradient(&self, is_north: bool) -> Gradient<Vec4> {
+        let base = if is_north { self.north } else { self.south };
+        let end = base.lerp(UniColor::white(), 0.4);
+        Colormap::custom(vec![(0.0, base), (1.0, end)])
+            .unwrap_or_default()
+            .to_fading_gradient()
+    }
+}
+
+/// Loads `.theme.ron` and `.theme.toml` files into [`Theme`] assets
+#[derive(Default)]
+pub struct ThemeLoader;
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> std::result::Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(Error::from_io)?;
+        let text = std::str::from_utf8(&bytes)
+            .map_err(|e| ResourceError::LoadFailed(format!("Theme is not valid UTF-8: {e}")))?;
+
+        let is_toml = load_context
+            .path()
+            .extension()
+            .is_some_and(|ext| ext == "toml");
+
+        let theme: Theme = if is_toml {
+            toml::from_str(text)
+                .map_err(|e| ResourceError::LoadFailed(format!("Invalid theme TOML: {e}")))?
+        } else {
+            ron::de::from_str(text)
+                .map_err(|e| ResourceError::LoadFailed(format!("Invalid theme RON: {e}")))?
+        };
+
+        theme.validate()?;
+        Ok(theme)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron", "theme.toml"]
+    }
+}
+
+/// Loaded theme handles and which one is active
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct ThemeSettings {
+    pub themes: Vec<Handle<Theme>>,
+    pub active: usize,
+    /// Set when the active theme must be (re)applied
+    pub dirty: bool,
+}
+
+impl ThemeSettings {
+    pub fn active_handle(&self) -> Option<&Handle<Theme>> {
+        self.themes.get(self.active)
+    }
+
+    pub fn cycle(&mut self) {
+        if self.themes.is_empty() {
+            return;
+        }
+        self.active = (self.active + 1) % self.themes.len();
+        self.dirty = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_themes_parse() {
+        let dark: Theme = ron::de::from_str(include_str!("../../assets/themes/dark.theme.ron")).unwrap();
+        let light: Theme = ron::de::from_str(include_str!("../../assets/themes/light.theme.ron")).unwrap();
+        let presentation: Theme = toml::from_str(include_str!("../../assets/themes/presentation.theme.toml")).unwrap();
+
+        for theme in [dark, light, presentation] {
+            assert!(theme.validate().is_ok(), "theme '{}' failed validation", theme.name);
+        }
+    }
+
+    #[test]
+    fn test_theme_cycle_wraps() {
+        let mut settings = ThemeSettings {
+            themes: vec![Handle::default(), Handle::default()],
+            ..default()
+        };
+        settings.cycle();
+        assert_eq!(settings.active, 1);
+        settings.cycle();
+        assert_eq!(settings.active, 0);
+        assert!(settings.dirty);
+    }
+}
@@ -2,10 +2,11 @@ use bevy::{
     prelude::*,
     render::color::LinearRgba,
 };
+use serde::{Deserialize, Serialize};
 
 /// A unified color type that handles conversions between different color formats
 /// needed across the application.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
 pub struct UniColor {
     pub r: f32,
     pub g: f32,
@@ -36,6 +37,132 @@ impl UniColor {
         Self::from(color)
     }
 
+    /// Create a new color from HSV values (hue in degrees, saturation and value in 0..1)
+    pub fn hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let c = value * saturation;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = hue_sector(h, c, x);
+        let m = value - c;
+        Self::srgb(r + m, g + m, b + m)
+    }
+
+    /// Create a new color from HSL values (hue in degrees, saturation and lightness in 0..1)
+    pub fn hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let c = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = hue_sector(h, c, x);
+        let m = lightness - c / 2.0;
+        Self::srgb(r + m, g + m, b + m)
+    }
+
+    /// Create a new color from OKLab values (perceptual lightness and the a/b opponent axes)
+    pub fn oklab(l: f32, a: f32, b: f32) -> Self {
+        let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+        let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+        let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+
+        let (l3, m3, s3) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+        let r = 4.076_741_7 * l3 - 3.307_711_6 * m3 + 0.230_969_94 * s3;
+        let g = -1.268_438 * l3 + 2.609_757_4 * m3 - 0.341_319_38 * s3;
+        let b = -0.004_196_086_3 * l3 - 0.703_418_6 * m3 + 1.707_614_7 * s3;
+
+        Self::srgb(
+            linear_to_srgb(r).clamp(0.0, 1.0),
+            linear_to_srgb(g).clamp(0.0, 1.0),
+            linear_to_srgb(b).clamp(0.0, 1.0),
+        )
+    }
+
+    /// Create a new color from a packed 0xRRGGBB value
+    pub fn hex(rgb: u32) -> Self {
+        Self::srgb(
+            ((rgb >> 16) & 0xff) as f32 / 255.0,
+            ((rgb >> 8) & 0xff) as f32 / 255.0,
+            (rgb & 0xff) as f32 / 255.0,
+        )
+    }
+
+    /// Get the color as (hue in degrees, saturation, value)
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let delta = max - min;
+        let saturation = if max > 0.0 { delta / max } else { 0.0 };
+        (self.hue(max, delta), saturation, max)
+    }
+
+    /// Get the color as (hue in degrees, saturation, lightness)
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let delta = max - min;
+        let lightness = (max + min) / 2.0;
+        let saturation = if delta > 0.0 {
+            delta / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        (self.hue(max, delta), saturation, lightness)
+    }
+
+    /// Get the color as OKLab (l, a, b)
+    pub fn to_oklab(&self) -> (f32, f32, f32) {
+        let r = srgb_to_linear(self.r);
+        let g = srgb_to_linear(self.g);
+        let b = srgb_to_linear(self.b);
+
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+        (
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+
+    /// Linearly interpolate between two colors in sRGB space
+    pub fn lerp(&self, other: UniColor, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Self::srgba(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
+
+    /// Interpolate between two colors in OKLab space, giving perceptually even steps
+    pub fn lerp_oklab(&self, other: UniColor, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let (l1, a1, b1) = self.to_oklab();
+        let (l2, a2, b2) = other.to_oklab();
+        Self::oklab(
+            l1 + (l2 - l1) * t,
+            a1 + (a2 - a1) * t,
+            b1 + (b2 - b1) * t,
+        )
+        .with_alpha(self.a + (other.a - self.a) * t)
+    }
+
+    fn hue(&self, max: f32, delta: f32) -> f32 {
+        if delta <= 0.0 {
+            return 0.0;
+        }
+        let hue = if max == self.r {
+            60.0 * ((self.g - self.b) / delta).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / delta + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / delta + 4.0)
+        };
+        hue.rem_euclid(360.0)
+    }
+
     /// Get the color as a Bevy Color (sRGB)
     pub fn as_bevy_color(&self) -> Color {
         Color::srgba(self.r, self.g, self.b, self.a)
@@ -52,6 +179,11 @@ impl UniColor {
         Vec4::new(self.r, self.g, self.b, self.a)
     }
 
+    /// Get the color as 8-bit sRGBA, e.g. for `Rgba8UnormSrgb` textures
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
     /// Get the color with a modified alpha value
     pub fn with_alpha(self, alpha: f32) -> Self {
         Self {
@@ -94,6 +226,34 @@ impl UniColor {
     }
 }
 
+/// Maps a hue sector (0..6) to unshifted RGB components
+fn hue_sector(h: f32, c: f32, x: f32) -> (f32, f32, f32) {
+    match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 // Conversion from Bevy Color
 impl From<Color> for UniColor {
     fn from(color: Color) -> Self {
@@ -116,24 +276,41 @@ impl From<UniColor> for Vec4 {
     }
 }
 
-/// Material color configuration with proper color handling
-#[derive(Resource)]
+/// Material color configuration with proper color handling.
+///
+/// This is the resolved colour set of the active `Theme`; systems read it
+/// instead of hardcoding colours so theme switches reach them.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
 pub struct MaterialColors {
     pub node: UniColor,
     pub connection: UniColor,
     pub highlight: UniColor,
     pub background: UniColor,
     pub emissive: UniColor,
+    pub north: UniColor,
+    pub south: UniColor,
 }
 
 impl Default for MaterialColors {
     fn default() -> Self {
         Self {
             node: UniColor::srgba(0.9, 0.9, 0.9, 0.9),
-            connection: UniColor::srgb(0.8, 0.2, 0.2),
-            highlight: UniColor::srgb(0.2, 0.2, 0.8),
+            connection: UniColor::srgb(0.3, 0.5, 0.8).with_alpha(0.8),
+            highlight: UniColor::srgb(1.0, 0.84, 0.0),
             background: UniColor::srgb(0.1, 0.1, 0.1),
-            emissive: UniColor::srgb(0.2, 0.2, 1.0),
+            emissive: UniColor::srgb(0.1, 0.1, 0.1),
+            north: UniColor::srgb(0.0, 0.5, 1.0),
+            south: UniColor::srgb(1.0, 0.2, 0.0),
+        }
+    }
+}
+
+impl MaterialColors {
+    pub fn polarity(&self, polarity: crate::components::Polarity) -> UniColor {
+        match polarity {
+            crate::components::Polarity::North => self.north,
+            crate::components::Polarity::South => self.south,
         }
     }
 }
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+/// Orthographic views shown alongside the perspective camera in split mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrthoAxis {
+    /// Looking down the helix axis
+    Top,
+    Front,
+    Side,
+}
+
+impl OrthoAxis {
+    pub const ALL: [OrthoAxis; 3] = [OrthoAxis::Top, OrthoAxis::Front, OrthoAxis::Side];
+
+    /// Direction the camera looks along
+    pub fn forward(self) -> Vec3 {
+        match self {
+            OrthoAxis::Top => Vec3::NEG_Y,
+            OrthoAxis::Front => Vec3::NEG_Z,
+            OrthoAxis::Side => Vec3::NEG_X,
+        }
+    }
+
+    /// Screen-up direction; top view keeps +X to the right
+    pub fn up(self) -> Vec3 {
+        match self {
+            OrthoAxis::Top => Vec3::NEG_Z,
+            OrthoAxis::Front | OrthoAxis::Side => Vec3::Y,
+        }
+    }
+
+    /// Quadrant index; the perspective view takes quadrant 0
+    pub fn quadrant(self) -> u32 {
+        match self {
+            OrthoAxis::Top => 1,
+            OrthoAxis::Front => 2,
+            OrthoAxis::Side => 3,
+        }
+    }
+}
+
+/// Whether the window is split into four viewports
+#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[reflect(Resource)]
+pub struct ViewportLayout {
+    pub split: bool,
+    /// Gap between viewports in physical pixels
+    pub gap: u32,
+}
+
+impl Default for ViewportLayout {
+    fn default() -> Self {
+        Self { split: false, gap: 2 }
+    }
+}
+
+impl ViewportLayout {
+    /// Physical position and size of `quadrant` (0 top-left, 1 top-right,
+    /// 2 bottom-left, 3 bottom-right) in a window of `size` physical pixels
+    pub fn quadrant(&self, size: UVec2, quadrant: u32) -> (UVec2, UVec2) {
+        let half = size / 2;
+        let column = quadrant % 2;
+        let row = quadrant / 2;
+        let position = UVec2::new(column * half.x, row * half.y);
+        // The right column and bottom row take any odd pixel
+        let extent = UVec2::new(
+            if column == 0 { half.x } else { size.x - half.x },
+            if row == 0 { half.y } else { size.y - half.y },
+        );
+        let gap = UVec2::splat(self.gap / 2).min(extent.saturating_sub(UVec2::ONE) / 2);
+
+        (position + gap, (extent - gap * 2).max(UVec2::ONE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quadrants_tile_the_window() {
+        let layout = ViewportLayout { split: true, gap: 0 };
+        let size = UVec2::new(1281, 721);
+
+        let area: u32 = (0..4)
+            .map(|quadrant| {
+                let (_, extent) = layout.quadrant(size, quadrant);
+                extent.x * extent.y
+            })
+            .sum();
+        assert_eq!(area, size.x * size.y);
+        assert_eq!(layout.quadrant(size, 3), (UVec2::new(640, 360), UVec2::new(641, 361)));
+
+        let gapped = ViewportLayout { split: true, gap: 4 };
+        assert_eq!(gapped.quadrant(size, 0), (UVec2::new(2, 2), UVec2::new(636, 356)));
+        assert_eq!(gapped.quadrant(UVec2::new(2, 2), 0).1, UVec2::ONE);
+    }
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy_hanabi::prelude::*;
+use crate::{
+    components::{CpuParticleSettings, MainCamera},
+    resources::particle_budget::{BudgetCulled, BudgetEntry, ParticleBudget},
+    systems::cpu_particles::CpuParticleStats,
+    err::ErrorManager,
+};
+
+/// Measure emitter capacity against the budget, cull distant emitters and
+/// publish the spawn scale other particle systems apply
+#[allow(clippy::too_many_arguments)]
+pub fn update_particle_budget(
+    mut commands: Commands,
+    mut budget: ResMut<ParticleBudget>,
+    assets: Res<Assets<EffectAsset>>,
+    stats: Res<CpuParticleStats>,
+    cameras: Query<&GlobalTransform, With<MainCamera>>,
+    mut effects: Query<(Entity, &ParticleEffect, &GlobalTransform, &mut Visibility, Has<BudgetCulled>)>,
+    cpu_emitters: Query<(Entity, &CpuParticleSettings, &GlobalTransform, Has<BudgetCulled>)>,
+    error_manager: Res<ErrorManager>,
+) {
+    if let Err(e) = budget.validate() {
+        error_manager.report_error(e);
+        return;
+    }
+
+    let camera = cameras
+        .get_single()
+        .map(|transform| transform.translation())
+        .unwrap_or(Vec3::ZERO);
+
+    let mut entries: Vec<BudgetEntry> = Vec::new();
+    for (entity, effect, transform, visibility, culled) in effects.iter() {
+        // Effects the user hid do not count; ones we hid still compete for the budget
+        if *visibility == Visibility::Hidden && !culled {
+            continue;
+        }
+        let capacity = assets.get(&effect.handle).map(|asset| asset.capacity()).unwrap_or(0);
+        entries.push(BudgetEntry {
+            entity,
+            capacity,
+            distance: transform.translation().distance(camera),
+        });
+    }
+    for (entity, settings, transform, _) in cpu_emitters.iter() {
+        entries.push(BudgetEntry {
+            entity,
+            capacity: settings.max_particles as u32,
+            distance: transform.translation().distance(camera),
+        });
+    }
+
+    let plan = budget.plan(&entries);
+
+    for (entity, _, _, mut visibility, was_culled) in effects.iter_mut() {
+        let cull = plan.culled.contains(&entity);
+        if cull && !was_culled {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).try_insert(BudgetCulled);
+        } else if !cull && was_culled {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<BudgetCulled>();
+        }
+    }
+    for (entity, _, _, was_culled) in cpu_emitters.iter() {
+        let cull = plan.culled.contains(&entity);
+        if cull && !was_culled {
+            commands.entity(entity).try_insert(BudgetCulled);
+        } else if !cull && was_culled {
+            commands.entity(entity).remove::<BudgetCulled>();
+        }
+    }
+
+    budget.requested = entries.iter().map(|entry| entry.capacity).sum();
+    budget.active_capacity = plan.active_capacity;
+    budget.cpu_live = stats.live;
+    budget.emitters = entries.len();
+    budget.culled = plan.culled.len();
+    budget.spawn_scale = plan.spawn_scale;
+}
@@ -1,91 +1,425 @@
-use bevy::{
-    prelude::*,
-    input::keyboard::KeyCode,
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy_tweening::{
+    lens::Lens, Animator, ComponentTarget, EaseFunction, Targetable, Tween, TweenCompleted, TweenState,
 };
+use leafwing_input_manager::prelude::*;
 use crate::{
-    components::MainCamera,
-    err::{Error, ErrorManager, ComponentError},
+    components::{MainCamera, Node},
+    resources::{
+        camera_bookmarks::BOOKMARK_SLOTS,
+        camera_path::{CameraPath, CameraPathPlayer, PathClock, PathPlayback, CAMERA_PATH_PATHS},
+        input_bindings::HyvoAction,
+        CameraBookmarks, CameraState, Selection,
+    },
 };
 
-pub fn setup_camera(
-    mut commands: Commands,
-    mut error_manager: ResMut<ErrorManager>,
+/// Radians of orbit per pixel of middle-mouse drag
+const ORBIT_SENSITIVITY: f32 = 0.005;
+/// Orbit radii of pan per pixel of shift + middle-mouse drag
+const PAN_SENSITIVITY: f32 = 0.0015;
+/// Radius change per second at full zoom input, as a log factor
+const ZOOM_SPEED: f32 = 1.5;
+/// Radians per second at full orbit input
+const KEY_ORBIT_SPEED: f32 = 1.2;
+/// Orbit radii per second at full pan input
+const KEY_PAN_SPEED: f32 = 0.5;
+/// Rotation applied by an orbit jump
+const ORBIT_JUMP: f32 = std::f32::consts::FRAC_PI_4;
+/// Duration of an animated fly-to
+const FLIGHT_DURATION: Duration = Duration::from_millis(900);
+/// Radius added around each node when framing, so shapes are not clipped
+const FRAMING_MARGIN: f32 = 1.0;
+
+/// Orbit jumps and the direction each one turns
+const ORBIT_JUMPS: [(HyvoAction, Vec2); 4] = [
+    (HyvoAction::OrbitJumpLeft, Vec2::NEG_X),
+    (HyvoAction::OrbitJumpRight, Vec2::X),
+    (HyvoAction::OrbitJumpUp, Vec2::Y),
+    (HyvoAction::OrbitJumpDown, Vec2::NEG_Y),
+];
+
+/// Where the camera currently is on its way to the `CameraState` targets
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OrbitCamera {
+    pub rotation: Quat,
+    pub radius: f32,
+    pub focus: Vec3,
+}
+
+impl OrbitCamera {
+    /// Start already at the targets
+    pub fn from_state(state: &CameraState) -> Self {
+        Self {
+            rotation: state.target_rotation,
+            radius: state.target_radius,
+            focus: state.target_focus,
+        }
+    }
+
+    /// Ease toward the targets; `t` is the fraction of the remaining gap to close
+    pub fn approach(&mut self, state: &CameraState, t: f32) {
+        self.rotation = self.rotation.slerp(state.target_rotation, t).normalize();
+        self.radius += (state.target_radius - self.radius) * t;
+        self.focus = self.focus.lerp(state.target_focus, t);
+    }
+
+    pub fn transform(&self) -> Transform {
+        CameraState::transform_for(self.focus, self.rotation, self.radius)
+    }
+}
+
+/// Tweens the whole orbit so the camera swings around the focus instead of
+/// cutting through it
+struct OrbitCameraLens {
+    start: OrbitCamera,
+    end: OrbitCamera,
+}
+
+impl Lens<OrbitCamera> for OrbitCameraLens {
+    fn lerp(&mut self, target: &mut dyn Targetable<OrbitCamera>, ratio: f32) {
+        target.rotation = self.start.rotation.slerp(self.end.rotation, ratio).normalize();
+        target.radius = self.start.radius + (self.end.radius - self.start.radius) * ratio;
+        target.focus = self.start.focus.lerp(self.end.focus, ratio);
+    }
+}
+
+/// Drags orbit and pan by the pixel, held inputs orbit, pan and zoom at a
+/// rate, and orbit jumps turn 45 degrees. Pan wins over orbit on shared keys.
+pub fn orbit_camera_input(
+    time: Res<Time<Real>>,
+    actions: Res<ActionState<HyvoAction>>,
+    mut state: ResMut<CameraState>,
 ) {
-    let camera_position = Vec3::new(-2.0, 2.5, 5.0);
-    let look_at = Vec3::ZERO;
+    let dt = time.delta_secs();
+
+    let pan_drag = actions.axis_pair(&HyvoAction::PanDrag);
+    let orbit_drag = actions.axis_pair(&HyvoAction::OrbitDrag);
+    if pan_drag != Vec2::ZERO {
+        state.pan(Vec2::new(-pan_drag.x, pan_drag.y) * PAN_SENSITIVITY);
+    } else if orbit_drag != Vec2::ZERO {
+        state.orbit(-orbit_drag.x * ORBIT_SENSITIVITY, orbit_drag.y * ORBIT_SENSITIVITY);
+    }
+
+    let zoom = actions.value(&HyvoAction::Zoom);
+    if zoom != 0.0 {
+        state.zoom((zoom * ZOOM_SPEED * dt).exp());
+    }
+
+    let jumping = ORBIT_JUMPS.iter().any(|(action, _)| actions.pressed(action));
+    let changing_speed = actions.pressed(&HyvoAction::SpeedUp) || actions.pressed(&HyvoAction::SpeedDown);
+    let jump: Vec2 = ORBIT_JUMPS
+        .iter()
+        .filter(|(action, _)| actions.just_pressed(action))
+        .map(|(_, direction)| *direction)
+        .sum();
+    let pan = actions.clamped_axis_pair(&HyvoAction::Pan);
+    let orbit = actions.clamped_axis_pair(&HyvoAction::Orbit);
+
+    // Jump and speed chords usually share keys with orbit and pan, so they take priority
+    if jumping || changing_speed {
+        if jump != Vec2::ZERO {
+            state.orbit(jump.x * ORBIT_JUMP, jump.y * ORBIT_JUMP);
+        }
+    } else if pan != Vec2::ZERO {
+        state.pan(pan * KEY_PAN_SPEED * dt);
+    } else if orbit != Vec2::ZERO {
+        state.orbit(orbit.x * KEY_ORBIT_SPEED * dt, orbit.y * KEY_ORBIT_SPEED * dt);
+    }
+}
 
-    if camera_position.distance(look_at) < 0.1 {
-        error_manager.report_error(Error::Component(ComponentError::ValidationFailed(
-            "Camera position too close to look_at point".into()
-        )));
+/// Move the camera toward the `CameraState` targets with frame-rate
+/// independent exponential smoothing, or along its flight. Uses real time so
+/// the camera stays responsive while the simulation is paused; flights tick
+/// their tween here rather than in bevy_tweening's virtual-time animator
+/// system so they still land then.
+pub fn apply_orbit_camera(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    state: Res<CameraState>,
+    player: Res<CameraPathPlayer>,
+    completed: ResMut<Events<TweenCompleted>>,
+    mut cameras: Query<(Entity, &mut OrbitCamera, &mut Transform, Option<&mut Animator<OrbitCamera>>), With<MainCamera>>,
+) {
+    // A camera path owns the camera until it stops
+    if player.playback != PathPlayback::Stopped {
         return;
     }
+    let t = 1.0 - (-state.smoothing.max(0.0) * time.delta_secs()).exp();
+    let mut completed: Mut<Events<TweenCompleted>> = completed.into();
 
-    commands.spawn((
-        Camera3dBundle {
-            transform: Transform::from_translation(camera_position)
-                .looking_at(look_at, Vec3::Y),
-            ..default()
-        },
-        MainCamera,
-    ));
+    for (entity, mut orbit, mut transform, animator) in cameras.iter_mut() {
+        match animator {
+            Some(mut animator) => {
+                let mut target = ComponentTarget::new(orbit.reborrow());
+                let flight = animator
+                    .tweenable_mut()
+                    .tick(time.delta(), &mut target, entity, &mut completed, &mut commands);
+                if flight == TweenState::Completed {
+                    commands.entity(entity).remove::<Animator<OrbitCamera>>();
+                }
+            }
+            None => orbit.approach(&state, t),
+        }
+        *transform = orbit.transform();
+    }
 }
 
-pub fn camera_controls(
-    keyboard: Res<Input<KeyCode>>,
-    time: Res<Time>,
-    mut query: Query<&mut Transform, With<MainCamera>>,
-    mut error_manager: ResMut<ErrorManager>,
+/// Saves the view to a bookmark slot, or flies back to one
+pub fn camera_bookmarks(
+    mut commands: Commands,
+    actions: Res<ActionState<HyvoAction>>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut state: ResMut<CameraState>,
+    cameras: Query<(Entity, &OrbitCamera), With<MainCamera>>,
 ) {
-    let mut camera_transform = match query.get_single_mut() {
-        Ok(transform) => transform,
-        Err(_) => {
-            error_manager.report_error(Error::Component(ComponentError::NotFound(
-                "Camera not found".into()
-            )));
-            return;
+    let slots = || 1..=BOOKMARK_SLOTS as u8;
+    if let Some(slot) = slots().find(|slot| actions.just_pressed(&HyvoAction::SaveBookmark(*slot))) {
+        if let Some(bookmark) = bookmarks.save(slot as usize, &state) {
+            info!("Saved camera to {} (slot {})", bookmark.name, slot);
         }
+        return;
+    }
+
+    let Some(slot) = slots().find(|slot| actions.just_pressed(&HyvoAction::Bookmark(*slot))) else {
+        return;
+    };
+    let Some(bookmark) = bookmarks.get(slot as usize) else {
+        return;
     };
+    bookmark.apply_to(&mut state);
+    for (entity, orbit) in cameras.iter() {
+        fly_to(&mut commands, entity, *orbit, &state);
+    }
+}
+
+/// Frames every node, or just the selected nodes
+pub fn frame_nodes(
+    mut commands: Commands,
+    actions: Res<ActionState<HyvoAction>>,
+    selection: Res<Selection>,
+    mut state: ResMut<CameraState>,
+    nodes: Query<(Entity, &GlobalTransform), With<Node>>,
+    cameras: Query<(Entity, &OrbitCamera, &Projection), With<MainCamera>>,
+) {
+    let frame_all = actions.just_pressed(&HyvoAction::FrameAll);
+    let frame_selected = actions.just_pressed(&HyvoAction::FrameSelection) && !selection.is_empty();
+    if !frame_all && !frame_selected {
+        return;
+    }
 
-    let mut movement = Vec3::ZERO;
-    let speed = 5.0;
-    let delta = time.delta_seconds();
+    let points: Vec<Vec3> = nodes
+        .iter()
+        .filter(|(entity, _)| frame_all || selection.contains(*entity))
+        .map(|(_, transform)| transform.translation())
+        .collect();
+    let Some((center, radius)) = bounding_sphere(&points) else {
+        return;
+    };
 
-    if keyboard.pressed(KeyCode::W) {
-        movement.z -= speed * delta;
+    for (entity, orbit, projection) in cameras.iter() {
+        let fov = match projection {
+            Projection::Perspective(perspective) => perspective.fov,
+            _ => std::f32::consts::FRAC_PI_4,
+        };
+        state.target_focus = center;
+        state.target_radius = framing_distance(radius + FRAMING_MARGIN, fov);
+        // Keeps the framed radius within the zoom limits
+        state.zoom(1.0);
+        fly_to(&mut commands, entity, *orbit, &state);
     }
-    if keyboard.pressed(KeyCode::S) {
-        movement.z += speed * delta;
+}
+
+pub fn load_camera_paths(
+    asset_server: Res<AssetServer>,
+    mut player: ResMut<CameraPathPlayer>,
+) {
+    player.paths = CAMERA_PATH_PATHS
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+    player.active = 0;
+}
+
+pub fn control_camera_path(
+    actions: Res<ActionState<HyvoAction>>,
+    mut player: ResMut<CameraPathPlayer>,
+) {
+    if actions.just_pressed(&HyvoAction::StopCameraPath) {
+        player.stop();
+    } else if actions.just_pressed(&HyvoAction::ToggleCameraPath) {
+        player.toggle_pause();
     }
-    if keyboard.pressed(KeyCode::A) {
-        movement.x -= speed * delta;
+}
+
+/// Drive the main camera along the active path. On stop, the orbit rig takes
+/// over from wherever the path left the camera.
+#[allow(clippy::too_many_arguments)]
+pub fn play_camera_path(
+    time: Res<Time>,
+    real_time: Res<Time<Real>>,
+    paths: Res<Assets<CameraPath>>,
+    mut player: ResMut<CameraPathPlayer>,
+    mut state: ResMut<CameraState>,
+    mut cameras: Query<(&mut Transform, &mut Projection, &mut OrbitCamera), With<MainCamera>>,
+    mut resume: Local<Option<(Vec3, f32)>>,
+) {
+    let Ok((mut transform, mut projection, mut orbit)) = cameras.get_single_mut() else {
+        return;
+    };
+
+    if player.playback == PathPlayback::Stopped {
+        // Hand back to the orbit rig, looking at where the path was looking
+        if let Some((look_at, fov)) = resume.take() {
+            if let Projection::Perspective(perspective) = projection.as_mut() {
+                perspective.fov = fov;
+            }
+            state.target_focus = look_at;
+            state.target_radius = transform.translation.distance(look_at);
+            state.target_rotation = transform.rotation;
+            *orbit = OrbitCamera::from_state(&state);
+        }
+        return;
     }
-    if keyboard.pressed(KeyCode::D) {
-        movement.x += speed * delta;
+
+    let Some(path) = player.active_handle().and_then(|handle| paths.get(handle)) else {
+        return;
+    };
+
+    if player.playback == PathPlayback::Playing {
+        let dt = match player.clock {
+            PathClock::Wall => real_time.delta_secs(),
+            // Virtual time already follows the simulation's pause and speed
+            PathClock::Simulation => time.delta_secs(),
+            PathClock::FixedStep(step) => step,
+        };
+        player.time += dt;
     }
-    if keyboard.pressed(KeyCode::Q) {
-        movement.y -= speed * delta;
+
+    let Some(sample) = path.sample(player.time) else {
+        return;
+    };
+    // Remember the FOV from before the path started so stopping restores it
+    let restore_fov = resume.map(|(_, fov)| fov).unwrap_or(match projection.as_ref() {
+        Projection::Perspective(perspective) => perspective.fov,
+        _ => std::f32::consts::FRAC_PI_4,
+    });
+    *resume = Some((sample.look_at, restore_fov));
+
+    *transform = sample.transform();
+    if let Projection::Perspective(perspective) = projection.as_mut() {
+        perspective.fov = sample.fov;
     }
-    if keyboard.pressed(KeyCode::E) {
-        movement.y += speed * delta;
+
+    if !path.looped && player.time >= path.duration() {
+        player.playback = PathPlayback::Stopped;
     }
+}
 
-    camera_transform.translation += movement;
+/// Tween `camera` from `from` to the targets in `state`
+fn fly_to(commands: &mut Commands, camera: Entity, from: OrbitCamera, state: &CameraState) {
+    let tween = Tween::new(
+        EaseFunction::CubicInOut,
+        FLIGHT_DURATION,
+        OrbitCameraLens {
+            start: from,
+            end: OrbitCamera::from_state(state),
+        },
+    );
+
+    commands.entity(camera).try_insert(Animator::new(tween));
+}
+
+/// Smallest sphere centred on the bounding box of `points` containing them all
+pub fn bounding_sphere(points: &[Vec3]) -> Option<(Vec3, f32)> {
+    let first = *points.first()?;
+    let (min, max) = points
+        .iter()
+        .fold((first, first), |(min, max), point| (min.min(*point), max.max(*point)));
+    let center = (min + max) * 0.5;
+    let radius = points
+        .iter()
+        .map(|point| point.distance(center))
+        .fold(0.0, f32::max);
+    Some((center, radius))
+}
+
+/// Camera distance at which a sphere of `radius` fills a vertical field of view `fov`
+pub fn framing_distance(radius: f32, fov: f32) -> f32 {
+    radius / (fov * 0.5).sin().max(f32::EPSILON)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bevy::app::App;
+    use bevy::time::TimeUpdateStrategy;
 
     #[test]
-    fn test_camera_setup() {
+    fn test_orbit_passes_over_pole_and_converges() {
+        let mut state = CameraState::default();
+        let focus = state.target_focus;
+
+        // Pitch well past straight up; a yaw/pitch rig would flip or clamp here
+        for _ in 0..8 {
+            state.orbit(0.0, 0.25);
+        }
+        let transform = state.target_transform();
+        assert!(transform.rotation.is_normalized());
+        assert!((transform.translation.distance(focus) - state.target_radius).abs() < 1e-3);
+        assert!(transform.forward().dot((focus - transform.translation).normalize()) > 0.999);
+
+        state.zoom(1e6);
+        assert_eq!(state.target_radius, CameraState::RADIUS_LIMITS.1);
+
+        let mut orbit = OrbitCamera::from_state(&CameraState::default());
+        for _ in 0..200 {
+            orbit.approach(&state, 0.2);
+        }
+        assert!(orbit.rotation.angle_between(state.target_rotation) < 1e-3);
+        assert!((orbit.radius - state.target_radius).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_framing() {
+        assert_eq!(bounding_sphere(&[]), None);
+        let (center, radius) = bounding_sphere(&[Vec3::new(-2.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0), Vec3::Y]).unwrap();
+        assert_eq!(center, Vec3::new(0.0, 0.5, 0.0));
+        assert!((radius - 2.0616).abs() < 1e-3);
+
+        // A 60 degree view fits a unit sphere at twice its radius
+        assert!((framing_distance(1.0, std::f32::consts::FRAC_PI_3) - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_flight_lands_while_paused() {
         let mut app = App::new();
-        app.init_resource::<ErrorManager>();
-        
-        app.add_systems(Startup, setup_camera);
-        app.update();
-        
-        assert!(app.world.query::<&Camera3d>().iter(&app.world).count() == 1);
-    }
-} 
\ No newline at end of file
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+        app.add_event::<TweenCompleted>();
+        app.init_resource::<CameraPathPlayer>();
+        app.add_systems(Update, apply_orbit_camera);
+        app.world_mut().resource_mut::<Time<Virtual>>().pause();
+
+        let target = CameraState {
+            target_focus: Vec3::new(3.0, 0.0, 0.0),
+            ..default()
+        };
+        let camera = app
+            .world_mut()
+            .spawn((MainCamera, OrbitCamera::from_state(&CameraState::default()), Transform::default()))
+            .id();
+        let mut commands = app.world_mut().commands();
+        fly_to(&mut commands, camera, OrbitCamera::from_state(&CameraState::default()), &target);
+        app.world_mut().flush();
+        app.insert_resource(target);
+
+        // The first update has no delta; nine more cover the 900ms flight
+        for _ in 0..10 {
+            app.update();
+        }
+
+        assert!(app.world().get::<Animator<OrbitCamera>>(camera).is_none());
+        let orbit = app.world().get::<OrbitCamera>(camera).unwrap();
+        assert!(orbit.focus.distance(target.target_focus) < 1e-4);
+    }
+}
@@ -0,0 +1,434 @@
+use bevy::{
+    prelude::*,
+    render::view::NoFrustumCulling,
+    utils::HashMap,
+};
+use bevy_hanabi::ParticleEffect;
+use crate::{
+    components::{
+        ChargedParticle, CpuParticleSettings, MagneticField, Particle, ParticleColorOverLife, ParticleEmitter,
+    },
+    resources::{BudgetCulled, LorentzConfig, ParticleBudget},
+    err::ErrorManager,
+    systems::particle_instancing::{ParticleInstance, ParticleInstances},
+};
+
+/// Live CPU particle counts, overall and per emitter
+#[derive(Resource, Default, Debug)]
+pub struct CpuParticleStats {
+    pub live: usize,
+    pub per_emitter: HashMap<Entity, usize>,
+}
+
+/// Spawn the unit quad every CPU particle is drawn as an instance of
+pub fn setup_cpu_particle_renderer(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.spawn((
+        Mesh3d(meshes.add(Rectangle::new(1.0, 1.0))),
+        ParticleInstances::default(),
+        Transform::default(),
+        Visibility::default(),
+        // Particles are nowhere near the quad's own bounds
+        NoFrustumCulling,
+    ));
+}
+
+/// Give every emitter that hanabi does not draw CPU settings, so emitters
+/// spawned under either backend keep their own path when it is switched
+pub fn assign_cpu_backend(
+    mut commands: Commands,
+    emitters: Query<(Entity, &ParticleEmitter), (Without<CpuParticleSettings>, Without<ParticleEffect>)>,
+) {
+    for (entity, emitter) in emitters.iter() {
+        commands
+            .entity(entity)
+            .try_insert(CpuParticleSettings::for_polarity(emitter.get_polarity()));
+    }
+}
+
+pub fn spawn_cpu_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    stats: Res<CpuParticleStats>,
+    budget: Option<Res<ParticleBudget>>,
+    mut emitters: Query<(Entity, &ParticleEmitter, &mut CpuParticleSettings, &GlobalTransform), Without<BudgetCulled>>,
+    error_manager: Res<ErrorManager>,
+) {
+    let spawn_scale = budget.map_or(1.0, |budget| budget.spawn_scale);
+    let dt = time.delta_secs();
+    let mut rng = rand::thread_rng();
+
+    for (entity, emitter, mut settings, transform) in emitters.iter_mut() {
+        if !emitter.is_active() {
+            continue;
+        }
+        if let Err(e) = settings.validate() {
+            error_manager.report_error(e);
+            continue;
+        }
+
+        settings.spawn_accumulator += settings.spawn_rate * spawn_scale * dt;
+        let requested = settings.spawn_accumulator.floor();
+        settings.spawn_accumulator -= requested;
+
+        let live = stats.per_emitter.get(&entity).copied().unwrap_or(0);
+        let count = (requested as usize).min(settings.max_particles.saturating_sub(live));
+
+        for _ in 0..count {
+            let (local_position, shape_direction) = emitter.shape.sample(&mut rng);
+            let local_direction = emitter.velocity.direction(local_position, shape_direction);
+            let position = transform.transform_point(local_position);
+            let direction = transform
+                .affine()
+                .transform_vector3(local_direction)
+                .normalize_or_zero();
+
+            let mut particle = commands.spawn((
+                Particle {
+                    lifetime: settings.particle_lifetime,
+                    max_lifetime: settings.particle_lifetime,
+                    velocity: direction * settings.speed,
+                    acceleration: settings.acceleration,
+                    size: settings.size,
+                    color: settings.start_color,
+                },
+                ParticleColorOverLife {
+                    start: settings.start_color,
+                    end: settings.end_color,
+                    emitter: entity,
+                },
+                Transform::from_translation(position),
+            ));
+
+            if settings.charge != 0.0 {
+                particle.insert(ChargedParticle { charge: settings.charge });
+            }
+        }
+    }
+}
+
+pub fn integrate_cpu_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut stats: ResMut<CpuParticleStats>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &ParticleColorOverLife)>,
+) {
+    let dt = time.delta_secs();
+    stats.live = 0;
+    stats.per_emitter.clear();
+
+    for (entity, mut particle, mut transform, color_over_life) in particles.iter_mut() {
+        if !step_particle(&mut particle, &mut transform.translation, dt) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        particle.interpolate_color(color_over_life.start, color_over_life.end);
+        particle.fade_out();
+
+        stats.live += 1;
+        *stats.per_emitter.entry(color_over_life.emitter).or_insert(0) += 1;
+    }
+}
+
+/// Bend charged particle velocities by the Lorentz force q/m (v × B), where B is
+/// summed over the nearest `max_influencing_nodes` fields within range
+pub fn steer_charged_particles(
+    time: Res<Time>,
+    config: Res<LorentzConfig>,
+    fields: Query<(&GlobalTransform, &MagneticField)>,
+    mut particles: Query<(&mut Particle, &Transform, &ChargedParticle)>,
+    error_manager: Res<ErrorManager>,
+) {
+    if let Err(e) = config.validate() {
+        error_manager.report_error(e);
+        return;
+    }
+
+    let dt = time.delta_secs();
+    let sources: Vec<(Vec3, MagneticField)> = fields
+        .iter()
+        .map(|(transform, field)| (transform.translation(), *field))
+        .collect();
+    if sources.is_empty() {
+        return;
+    }
+
+    let mut nearby: Vec<(f32, usize)> = Vec::with_capacity(sources.len());
+    for (mut particle, transform, charged) in particles.iter_mut() {
+        let position = transform.translation;
+        let field = field_at_point(position, &sources, &config, &mut nearby);
+        particle.velocity = boris_rotate(particle.velocity, field, config.charge_to_mass * charged.charge, dt);
+    }
+}
+
+/// Summed field at `point` from the nearest sources; `nearby` is reused scratch space
+pub fn field_at_point(
+    point: Vec3,
+    sources: &[(Vec3, MagneticField)],
+    config: &LorentzConfig,
+    nearby: &mut Vec<(f32, usize)>,
+) -> Vec3 {
+    let radius_squared = config.influence_radius * config.influence_radius;
+
+    nearby.clear();
+    nearby.extend(
+        sources
+            .iter()
+            .enumerate()
+            .map(|(index, (origin, _))| (origin.distance_squared(point), index))
+            .filter(|(distance_squared, _)| *distance_squared <= radius_squared),
+    );
+    if nearby.len() > config.max_influencing_nodes {
+        nearby.select_nth_unstable_by(config.max_influencing_nodes, |a, b| a.0.total_cmp(&b.0));
+        nearby.truncate(config.max_influencing_nodes);
+    }
+
+    nearby
+        .iter()
+        .map(|(_, index)| {
+            let (origin, field) = &sources[*index];
+            field.field_at(*origin, point)
+        })
+        .sum()
+}
+
+/// Rotate `velocity` about `field` as the magnetic part of a Boris push. Speed is
+/// preserved exactly, so particles spiral without gaining or losing energy.
+pub fn boris_rotate(velocity: Vec3, field: Vec3, charge_to_mass: f32, dt: f32) -> Vec3 {
+    let t = field * (charge_to_mass * dt * 0.5);
+    let s = t * (2.0 / (1.0 + t.length_squared()));
+    let v_prime = velocity + velocity.cross(t);
+    velocity + v_prime.cross(s)
+}
+
+/// Advance one particle by `dt` with semi-implicit Euler. Returns false once it has expired.
+pub fn step_particle(particle: &mut Particle, position: &mut Vec3, dt: f32) -> bool {
+    particle.lifetime -= dt;
+    if particle.lifetime <= 0.0 {
+        return false;
+    }
+
+    particle.velocity += particle.acceleration * dt;
+    *position += particle.velocity * dt;
+    position.is_finite()
+}
+
+/// Refresh the per-instance data drawn by `ParticleInstancingPlugin`
+pub fn render_cpu_particles(
+    particles: Query<(&Particle, &Transform)>,
+    mut batches: Query<&mut ParticleInstances>,
+) {
+    for mut instances in batches.iter_mut() {
+        instances.0.clear();
+        instances.0.extend(
+            particles
+                .iter()
+                .map(|(particle, transform)| ParticleInstance::new(transform.translation, particle.size, particle.color)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::{EmitterShape, EmitterVelocity},
+        resources::{theme::{Theme, ThemeSettings}, EffectRegistry, ParticleBackend},
+        systems::particles::{spawn_requested_emitters, SpawnEmitter},
+    };
+    use std::time::Duration;
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_hanabi::EffectAsset;
+
+    #[test]
+    fn test_step_particle_expires() {
+        let mut particle = Particle {
+            lifetime: 0.1,
+            max_lifetime: 0.1,
+            velocity: Vec3::X,
+            acceleration: Vec3::ZERO,
+            ..default()
+        };
+        let mut position = Vec3::ZERO;
+
+        assert!(step_particle(&mut particle, &mut position, 0.05));
+        assert!((position.x - 0.05).abs() < 1e-6);
+        assert!(!step_particle(&mut particle, &mut position, 0.1));
+    }
+
+    #[test]
+    fn test_emitter_shapes_sample_inside_bounds() {
+        let mut rng = rand::thread_rng();
+        let shapes = [
+            EmitterShape::Sphere { radius: 2.0 },
+            EmitterShape::Cone { height: 1.0, radius: 0.5 },
+            EmitterShape::Box { size: Vec3::new(1.0, 2.0, 3.0) },
+            EmitterShape::Torus { major_radius: 2.0, minor_radius: 0.5 },
+            EmitterShape::Line { start: Vec3::ZERO, end: Vec3::X },
+            EmitterShape::Ellipsoid { half_extents: Vec3::new(1.0, 2.0, 0.5) },
+            EmitterShape::mesh_surface(&Cuboid::new(1.0, 2.0, 3.0).into(), 64, &mut rng).unwrap(),
+        ];
+
+        for shape in shapes.iter() {
+            for _ in 0..256 {
+                let (position, direction) = shape.sample(&mut rng);
+                assert!((direction.length() - 1.0).abs() < 1e-4);
+                match shape {
+                    EmitterShape::Sphere { radius } => assert!(position.length() <= *radius + 1e-4),
+                    EmitterShape::Cone { height, radius } => {
+                        assert!(position.y >= 0.0 && position.y <= *height);
+                        let allowed = radius * (1.0 - position.y / height);
+                        assert!(Vec2::new(position.x, position.z).length() <= allowed + 1e-4);
+                    }
+                    EmitterShape::Box { size } => assert!(position.abs().cmple(*size * 0.5 + 1e-4).all()),
+                    EmitterShape::Torus { major_radius, minor_radius } => {
+                        let ring = Vec2::new(position.x, position.z).length() - major_radius;
+                        assert!((Vec2::new(ring, position.y).length() - minor_radius).abs() < 1e-4);
+                    }
+                    EmitterShape::Line { start, end } => {
+                        assert!((position.distance(*start) + position.distance(*end) - start.distance(*end)).abs() < 1e-4);
+                    }
+                    EmitterShape::Ellipsoid { half_extents } => {
+                        assert!(((position / *half_extents).length() - 1.0).abs() < 1e-4);
+                    }
+                    EmitterShape::MeshSurface { half_extents, .. } => {
+                        assert!(half_extents.cmple(Vec3::new(0.5, 1.0, 1.5) + 1e-4).all());
+                        assert!(position.abs().cmple(*half_extents + 1e-4).all());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lorentz_steering_preserves_speed_and_bends_path() {
+        let velocity = Vec3::new(1.0, 0.0, 0.5);
+        let field = Vec3::new(0.0, 2.0, 0.0);
+        let rotated = boris_rotate(velocity, field, 3.0, 0.016);
+
+        assert!((rotated.length() - velocity.length()).abs() < 1e-5);
+        assert!(rotated.distance(velocity) > 0.0);
+        // Motion along the field line is unaffected
+        assert!((rotated.y - velocity.y).abs() < 1e-6);
+        assert_eq!(boris_rotate(Vec3::Y, field, 3.0, 0.016), Vec3::Y);
+    }
+
+    #[test]
+    fn test_field_uses_nearest_nodes_only() {
+        let field = MagneticField::default();
+        let sources = vec![
+            (Vec3::new(1.0, 0.0, 0.0), field),
+            (Vec3::new(2.0, 0.0, 0.0), field),
+            (Vec3::new(50.0, 0.0, 0.0), field),
+        ];
+        let mut scratch = Vec::new();
+        let config = LorentzConfig {
+            max_influencing_nodes: 1,
+            ..default()
+        };
+
+        let nearest = field_at_point(Vec3::ZERO, &sources, &config, &mut scratch);
+        assert_eq!(nearest, field.field_at(sources[0].0, Vec3::ZERO));
+
+        let config = LorentzConfig {
+            max_influencing_nodes: 8,
+            ..default()
+        };
+        let both = field_at_point(Vec3::ZERO, &sources, &config, &mut scratch);
+        let expected = field.field_at(sources[0].0, Vec3::ZERO) + field.field_at(sources[1].0, Vec3::ZERO);
+        assert!(both.distance(expected) < 1e-5);
+    }
+
+    #[test]
+    fn test_emitter_velocity_modes() {
+        let position = Vec3::new(1.0, 0.0, 0.0);
+
+        assert_eq!(EmitterVelocity::Radial.direction(position, Vec3::Y), Vec3::X);
+        assert_eq!(EmitterVelocity::Radial.direction(Vec3::ZERO, Vec3::Y), Vec3::Y);
+
+        let tangential = EmitterVelocity::Tangential { axis: Vec3::Y }.direction(position, Vec3::Y);
+        assert!(tangential.dot(position).abs() < 1e-6 && tangential.dot(Vec3::Y).abs() < 1e-6);
+
+        // On the dipole axis field lines point along the moment; at the equator they point back
+        let along = EmitterVelocity::AlongField { axis: Vec3::X };
+        assert!(along.direction(Vec3::X * 2.0, Vec3::Y).distance(Vec3::X) < 1e-6);
+        assert!(along.direction(Vec3::Z * 2.0, Vec3::Y).distance(-Vec3::X) < 1e-6);
+    }
+
+    #[test]
+    fn test_headless_emitter_spawns_particles() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+        app.init_resource::<ErrorManager>();
+        app.init_resource::<CpuParticleStats>();
+        app.add_systems(Update, assign_cpu_backend);
+        app.add_systems(Update, spawn_cpu_particles);
+
+        app.world_mut().spawn((ParticleEmitter::default(), GlobalTransform::default()));
+
+        // The first update has no delta and inserts CPU settings; each later
+        // 100ms update spawns 50/s * 0.1s = 5 particles
+        for _ in 0..5 {
+            app.update();
+        }
+
+        let count = app.world_mut().query::<&Particle>().iter(app.world()).count();
+        assert_eq!(count, 20);
+    }
+
+    #[test]
+    fn test_spawned_emitter_particles_are_steered() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+        app.init_resource::<Assets<EffectAsset>>();
+        app.init_resource::<Assets<Theme>>();
+        app.init_resource::<EffectRegistry>();
+        app.init_resource::<ThemeSettings>();
+        app.init_resource::<ErrorManager>();
+        app.init_resource::<CpuParticleStats>();
+        app.init_resource::<LorentzConfig>();
+        app.init_resource::<ParticleBackend>();
+        app.add_event::<SpawnEmitter>();
+        app.add_systems(
+            Update,
+            (
+                spawn_requested_emitters,
+                assign_cpu_backend,
+                spawn_cpu_particles,
+                steer_charged_particles,
+                integrate_cpu_particles,
+                render_cpu_particles,
+            )
+                .chain(),
+        );
+
+        app.world_mut().spawn((ParticleInstances::default(), Transform::default()));
+        app.world_mut().spawn((MagneticField { strength: 4.0, ..default() }, GlobalTransform::default()));
+        app.world_mut().send_event(SpawnEmitter {
+            position: Vec3::ZERO,
+            emitter: ParticleEmitter::default(),
+        });
+        for _ in 0..5 {
+            app.update();
+        }
+
+        // Radial particles from an emitter at the origin would keep moving
+        // straight away from it; steered ones have turned off that line
+        let mut particles = app.world_mut().query::<(&Particle, &Transform, &ChargedParticle)>();
+        let particles: Vec<_> = particles.iter(app.world()).collect();
+        assert!(!particles.is_empty());
+        let bent = particles
+            .iter()
+            .filter(|(particle, transform, _)| {
+                assert!((particle.velocity.length() - 1.0).abs() < 1e-3);
+                particle.velocity.normalize().cross(transform.translation.normalize()).length() > 0.05
+            })
+            .count();
+        assert!(bent * 4 >= particles.len() * 3);
+
+        let mut instances = app.world_mut().query::<&ParticleInstances>();
+        assert_eq!(instances.single(app.world()).0.len(), particles.len());
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_hanabi::prelude::*;
+use crate::{
+    resources::{
+        effect_definition::{EffectDefinition, EffectLibrary},
+        theme::{Theme, ThemeSettings},
+        EffectKind, EffectRegistry,
+    },
+    err::{Error, ErrorManager, ResourceError},
+};
+
+pub fn load_effect_definitions(
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<EffectLibrary>,
+    mut registry: ResMut<EffectRegistry>,
+    effects: Res<Assets<EffectAsset>>,
+) {
+    library.definitions = EffectKind::BUILT_IN
+        .iter()
+        .filter_map(|kind| Some((*kind, asset_server.load(kind.definition_path()?))))
+        .collect();
+
+    // Only the fallback is built in code; every other built-in effect gets a
+    // reserved handle that its definition fills once loaded
+    for kind in EffectKind::BUILT_IN {
+        registry.get_or_reserve(kind, &effects);
+    }
+}
+
+/// Rebuild effect assets in place when their definition loads, is edited on
+/// disk, or the theme changes. Instances keep their handles, so running effects
+/// pick up the new asset without respawning.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_effect_definitions(
+    mut definition_events: EventReader<AssetEvent<EffectDefinition>>,
+    mut library: ResMut<EffectLibrary>,
+    definitions: Res<Assets<EffectDefinition>>,
+    theme_settings: Res<ThemeSettings>,
+    themes: Res<Assets<Theme>>,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    registry: Res<EffectRegistry>,
+    error_manager: Res<ErrorManager>,
+    mut pending: Local<HashSet<EffectKind>>,
+) {
+    for event in definition_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            if let Some(kind) = library.kind_of(*id) {
+                pending.insert(kind);
+            }
+        }
+    }
+    if library.dirty {
+        library.dirty = false;
+        let kinds: Vec<EffectKind> = library.definitions.keys().copied().collect();
+        pending.extend(kinds);
+    }
+    if pending.is_empty() {
+        return;
+    }
+
+    let theme = theme_settings
+        .active_handle()
+        .and_then(|handle| themes.get(handle))
+        .cloned()
+        .unwrap_or_default();
+
+    let kinds: Vec<EffectKind> = pending.iter().copied().collect();
+    for kind in kinds {
+        let target = registry.get(kind).map(|handle| handle.id());
+        let definition = library
+            .definitions
+            .get(&kind)
+            .and_then(|handle| definitions.get(handle));
+
+        // Wait for both the registered handle and the definition; the code-built
+        // fallback stays in place until then
+        let (Some(target), Some(definition)) = (target, definition) else {
+            continue;
+        };
+        pending.remove(&kind);
+
+        match definition.build(&theme) {
+            Ok(effect) => {
+                effects.insert(target, effect);
+                debug!("Rebuilt effect '{}' for {:?}", definition.name, kind);
+            }
+            Err(e) => error_manager.report_with_recovery(e, "Keeping the previous effect"),
+        }
+    }
+}
+
+/// Reports definitions that failed to load or parse
+pub fn report_effect_definition_errors(
+    asset_server: Res<AssetServer>,
+    library: Res<EffectLibrary>,
+    error_manager: Res<ErrorManager>,
+    mut reported: Local<Vec<AssetId<EffectDefinition>>>,
+) {
+    for handle in library.definitions.values() {
+        let failed = matches!(
+            asset_server.get_load_state(handle.id()),
+            Some(bevy::asset::LoadState::Failed(_))
+        );
+        if failed && !reported.contains(&handle.id()) {
+            reported.push(handle.id());
+            error_manager.report_error(Error::Resource(ResourceError::LoadFailed(format!(
+                "Effect definition {:?} failed to load",
+                handle.path()
+            ))));
+        } else if !failed {
+            reported.retain(|id| *id != handle.id());
+        }
+    }
+}
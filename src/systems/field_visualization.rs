@@ -0,0 +1,280 @@
+use bevy::{
+    prelude::*,
+    math::primitives::InfinitePlane3d,
+    picking::{
+        events::{Drag, Pointer},
+        focus::PickingInteraction,
+        mesh_picking::ray_cast::RayCastBackfaces,
+        pointer::PointerButton,
+    },
+    render::{
+        mesh::{Indices, Mesh},
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat},
+    },
+};
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    components::{MagneticField, MainCamera},
+    resources::{
+        field_visualization::{FieldVisualization, FieldVisualizationMode, SliceAxis},
+        input_bindings::HyvoAction,
+        Colormap,
+    },
+    err::ErrorManager,
+};
+
+/// Fields weaker than this draw no arrow
+const MIN_GLYPH_MAGNITUDE: f32 = 1e-4;
+/// Arrow length as a fraction of the spacing between samples
+const GLYPH_SCALE: f32 = 0.8;
+
+/// The draggable slice plane showing the field magnitude as a texture
+#[derive(Component)]
+pub struct FieldSlice {
+    pub image: Handle<Image>,
+    pub mesh: Handle<Mesh>,
+    /// Layout the mesh and texture were last built for
+    built: Option<(SliceAxis, Vec2, u32)>,
+}
+
+pub fn setup_field_slice(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let image = images.add(heat_image(2));
+    let mesh = meshes.add(slice_mesh(SliceAxis::default(), Vec2::ONE));
+
+    let material = materials.add(StandardMaterial {
+        base_color_texture: Some(image.clone()),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        cull_mode: None,
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(mesh.clone()),
+        MeshMaterial3d(material),
+        Transform::default(),
+        Visibility::Hidden,
+        // The plane is grabbed from whichever side faces the camera
+        RayCastBackfaces,
+        // Lets box selection tell a press on the slice from one on empty space
+        PickingInteraction::default(),
+        FieldSlice { image, mesh, built: None },
+    ));
+}
+
+/// Cycles off / grid / slice, or changes the slice axis
+pub fn control_field_visualization(
+    actions: Res<ActionState<HyvoAction>>,
+    mut visualization: ResMut<FieldVisualization>,
+) {
+    if actions.just_pressed(&HyvoAction::CycleSliceAxis) {
+        visualization.slice_axis = visualization.slice_axis.next();
+        let offset = visualization.slice_offset;
+        visualization.set_slice_offset(offset);
+    } else if actions.just_pressed(&HyvoAction::CycleFieldView) {
+        visualization.mode = visualization.mode.next();
+    }
+}
+
+/// Dragging the slice plane moves it along its axis only
+pub fn drag_field_slice(
+    mut drag_events: EventReader<Pointer<Drag>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    slices: Query<(), With<FieldSlice>>,
+    mut visualization: ResMut<FieldVisualization>,
+) {
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+
+    for event in drag_events.read() {
+        if event.button != PointerButton::Primary || !slices.contains(event.target) {
+            continue;
+        }
+        let Ok(ray) = camera.viewport_to_world(camera_transform, event.pointer_location.position) else {
+            continue;
+        };
+
+        // Intersect with the plane that contains the slice axis and faces the camera most
+        let (axis, _, _) = visualization.slice_axis.basis();
+        let forward = camera_transform.forward().as_vec3();
+        let Ok(normal) = Dir3::new(forward - axis * forward.dot(axis)) else {
+            // Looking straight down the axis; there is nothing to drag along
+            continue;
+        };
+
+        let origin = visualization.slice_center();
+        if let Some(distance) = ray.intersect_plane(origin, InfinitePlane3d::new(normal)) {
+            let offset = (ray.get_point(distance) - visualization.center).dot(axis);
+            visualization.set_slice_offset(offset);
+        }
+    }
+}
+
+/// Show, place and repaint the slice plane
+pub fn update_field_slice(
+    visualization: Res<FieldVisualization>,
+    sources: Query<(&GlobalTransform, &MagneticField)>,
+    mut slices: Query<(&mut FieldSlice, &mut Transform, &mut Visibility)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    error_manager: Res<ErrorManager>,
+) {
+    let Ok((mut slice, mut transform, mut visibility)) = slices.get_single_mut() else {
+        return;
+    };
+
+    if visualization.mode != FieldVisualizationMode::Slice {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    if let Err(e) = visualization.validate() {
+        if visualization.is_changed() {
+            error_manager.report_error(e);
+        }
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+    transform.translation = visualization.slice_center();
+
+    let layout = (visualization.slice_axis, visualization.slice_half_size(), visualization.slice_resolution);
+    if slice.built != Some(layout) {
+        meshes.insert(&slice.mesh, slice_mesh(layout.0, layout.1));
+        images.insert(&slice.image, heat_image(layout.2));
+        slice.built = Some(layout);
+    }
+
+    let Some(image) = images.get_mut(&slice.image) else {
+        return;
+    };
+    let sources: Vec<(Vec3, MagneticField)> = sources
+        .iter()
+        .map(|(transform, field)| (transform.translation(), *field))
+        .collect();
+    let colormap = Colormap::preset(visualization.colormap);
+
+    for (texel, point) in image
+        .data
+        .chunks_exact_mut(4)
+        .zip(visualization.slice_points(visualization.slice_resolution))
+    {
+        let magnitude = combined_field(&sources, point).length();
+        let fraction = visualization.magnitude_fraction(magnitude);
+        // Weak regions fade out so the geometry behind stays visible
+        let color = colormap.sample(fraction).with_alpha(0.25 + 0.6 * fraction);
+        texel.copy_from_slice(&color.to_rgba8());
+    }
+}
+
+/// Arrow glyphs pointing along the field, coloured and sized by magnitude
+pub fn draw_field_glyphs(
+    visualization: Res<FieldVisualization>,
+    sources: Query<(&GlobalTransform, &MagneticField)>,
+    mut gizmos: Gizmos,
+) {
+    let points = match visualization.mode {
+        FieldVisualizationMode::Off => return,
+        FieldVisualizationMode::Grid => visualization.grid_points(),
+        FieldVisualizationMode::Slice => visualization.slice_points(visualization.grid_resolution),
+    };
+    if visualization.validate().is_err() {
+        return;
+    }
+
+    let sources: Vec<(Vec3, MagneticField)> = sources
+        .iter()
+        .map(|(transform, field)| (transform.translation(), *field))
+        .collect();
+    if sources.is_empty() {
+        return;
+    }
+
+    let colormap = Colormap::preset(visualization.colormap);
+    let spacing = match visualization.mode {
+        FieldVisualizationMode::Slice => {
+            visualization.slice_half_size().min_element() * 2.0 / visualization.grid_resolution as f32
+        }
+        _ => visualization.grid_spacing(),
+    };
+
+    for point in points {
+        let field = combined_field(&sources, point);
+        let magnitude = field.length();
+        if magnitude < MIN_GLYPH_MAGNITUDE {
+            continue;
+        }
+        let fraction = visualization.magnitude_fraction(magnitude);
+        let length = spacing * GLYPH_SCALE * (0.3 + 0.7 * fraction);
+        let half = field / magnitude * length * 0.5;
+
+        gizmos
+            .arrow(point - half, point + half, colormap.sample(fraction).as_bevy_color())
+            .with_tip_length(length * 0.3);
+    }
+}
+
+/// Superposition of every node's dipole field at `point`
+pub fn combined_field(sources: &[(Vec3, MagneticField)], point: Vec3) -> Vec3 {
+    sources
+        .iter()
+        .map(|(origin, field)| field.field_at(*origin, point))
+        .sum()
+}
+
+/// Quad centred on the origin spanning the slice's `u` and `v` axes, with UVs
+/// laid out to match `FieldVisualization::slice_points`
+fn slice_mesh(axis: SliceAxis, half_size: Vec2) -> Mesh {
+    let (normal, u, v) = axis.basis();
+    let (u, v) = (u * half_size.x, v * half_size.y);
+
+    let positions: Vec<[f32; 3]> = [-u - v, u - v, u + v, -u + v]
+        .iter()
+        .map(|corner| corner.to_array())
+        .collect();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![normal.to_array(); 4]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
+    mesh.insert_indices(Indices::U32(vec![0, 1, 2, 0, 2, 3]));
+    mesh
+}
+
+fn heat_image(resolution: u32) -> Image {
+    Image::new_fill(
+        Extent3d {
+            width: resolution,
+            height: resolution,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Polarity;
+
+    #[test]
+    fn test_combined_field_superposes_dipoles() {
+        let field = MagneticField { polarity: Polarity::North, ..default() };
+        let opposite = MagneticField { polarity: Polarity::South, ..default() };
+        let point = Vec3::new(0.0, 2.0, 0.0);
+
+        let single = combined_field(&[(Vec3::ZERO, field)], point);
+        assert!(single.length() > 0.0);
+        assert_eq!(combined_field(&[(Vec3::ZERO, field), (Vec3::ZERO, field)], point), single * 2.0);
+        assert!(combined_field(&[(Vec3::ZERO, field), (Vec3::ZERO, opposite)], point).length() < 1e-6);
+    }
+}
@@ -1,20 +1,32 @@
 use bevy::{
     prelude::*,
+    math::primitives::{Capsule3d, Cone, Cylinder},
     render::{mesh::Indices, render_resource::PrimitiveTopology, render_asset::RenderAssetUsages},
 };
 use crate::{
-    components::{Node, Mesh3d, MeshMaterial3d},
-    resources::{HelixConfig, MaterialHandles},
+    components::{Connection, MagneticField, Node, ShapeType, Mesh3d, MeshMaterial3d},
+    resources::{HelixConfig, MaterialHandles, RegenerateHelix, Selection},
     err::{Error, ErrorManager, ComponentError},
 };
 
+/// Replace every node and connection with a freshly generated helix when a
+/// `RegenerateHelix` event arrives. An invalid config keeps the current one.
+#[allow(clippy::too_many_arguments)]
 pub fn generate_helix(
     mut commands: Commands,
+    mut requests: EventReader<RegenerateHelix>,
     config: Res<HelixConfig>,
     materials: Res<MaterialHandles>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut selection: ResMut<Selection>,
+    existing: Query<Entity, Or<(With<Node>, With<Connection>)>>,
     mut error_manager: ResMut<ErrorManager>,
 ) {
+    if requests.is_empty() {
+        return;
+    }
+    requests.clear();
+
     if config.radius <= 0.0 {
         error_manager.report_error(Error::Component(ComponentError::ValidationFailed(
             "Helix radius must be positive".into()
@@ -30,6 +42,11 @@ pub fn generate_helix(
         }
     };
 
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    selection.clear();
+
     let mesh_handle = meshes.add(mesh);
 
     commands.spawn((
@@ -43,6 +60,40 @@ pub fn generate_helix(
     ));
 }
 
+/// Mesh for a node of the given shape, sized by `ShapeType::dimensions`
+pub fn node_shape_mesh(shape_type: ShapeType) -> Mesh {
+    let (radius, length) = shape_type.dimensions();
+    match shape_type {
+        ShapeType::Alpha => Mesh::from(Capsule3d::new(radius, length)),
+        ShapeType::Beta => Mesh::from(Cylinder::new(radius, length)),
+        ShapeType::Gamma => Mesh::from(Cone { radius, height: length }),
+    }
+}
+
+/// Spawn a renderable, pickable node with its own `MagneticField` component
+pub fn spawn_node(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &MaterialHandles,
+    shape_type: ShapeType,
+    field: MagneticField,
+    transform: Transform,
+) -> Entity {
+    commands
+        .spawn((
+            Node {
+                shape_type,
+                magnetic_field: field,
+                ..default()
+            },
+            field,
+            bevy::prelude::Mesh3d(meshes.add(node_shape_mesh(shape_type))),
+            bevy::prelude::MeshMaterial3d(materials.node_material.clone()),
+            transform,
+        ))
+        .id()
+}
+
 #[derive(Bundle)]
 pub struct NodeBundle {
     pub node: Node,
@@ -0,0 +1,257 @@
+use bevy::{
+    prelude::*,
+    ui::RelativeCursorPosition,
+    utils::HashMap,
+};
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    components::{Connection, MagneticField, Node},
+    resources::{
+        history::{ConnectionSnapshot, NodeSnapshot, RestoreSnapshot, SimulationHistory, SimulationSnapshot},
+        input_bindings::HyvoAction,
+        uni_color::UniColor,
+        AnimationState, MaterialHandles, Selection,
+    },
+    systems::generation::spawn_node,
+};
+
+/// Root of the timeline overlay
+#[derive(Component)]
+pub struct Timeline;
+
+/// The bar that is clicked or dragged to scrub
+#[derive(Component)]
+pub struct TimelineTrack;
+
+/// Marks the snapshot being shown
+#[derive(Component)]
+pub struct TimelineHandle;
+
+#[derive(Component)]
+pub struct TimelineLabel;
+
+pub fn setup_timeline(mut commands: Commands) {
+    let track_color = UniColor::srgb(0.2, 0.2, 0.25).with_alpha(0.8);
+    let handle_color = UniColor::srgb(0.4, 0.7, 1.0);
+
+    commands
+        .spawn((
+            bevy::ui::Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(10.0),
+                right: Val::Percent(10.0),
+                bottom: Val::Px(16.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            Timeline,
+        ))
+        .with_children(|timeline| {
+            timeline.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(handle_color.as_bevy_color()),
+                TimelineLabel,
+            ));
+            timeline
+                .spawn((
+                    bevy::ui::Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(12.0),
+                        ..default()
+                    },
+                    BackgroundColor(track_color.as_bevy_color()),
+                    Interaction::default(),
+                    RelativeCursorPosition::default(),
+                    TimelineTrack,
+                ))
+                .with_children(|track| {
+                    track.spawn((
+                        bevy::ui::Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Px(6.0),
+                            height: Val::Percent(100.0),
+                            margin: UiRect::left(Val::Px(-3.0)),
+                            left: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(handle_color.as_bevy_color()),
+                        TimelineHandle,
+                    ));
+                });
+        });
+}
+
+pub fn toggle_timeline(
+    actions: Res<ActionState<HyvoAction>>,
+    mut timelines: Query<&mut Visibility, With<Timeline>>,
+) {
+    if !actions.just_pressed(&HyvoAction::ToggleTimeline) {
+        return;
+    }
+    for mut visibility in timelines.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+/// Pressing or dragging on the track rewinds to the nearest snapshot
+pub fn scrub_timeline(
+    tracks: Query<(&Interaction, &RelativeCursorPosition, &InheritedVisibility), With<TimelineTrack>>,
+    history: Res<SimulationHistory>,
+    mut restore: EventWriter<RestoreSnapshot>,
+) {
+    for (interaction, cursor, visibility) in tracks.iter() {
+        if *interaction != Interaction::Pressed || !visibility.get() {
+            continue;
+        }
+        let Some(index) = cursor.normalized.and_then(|position| history.index_at(position.x)) else {
+            continue;
+        };
+        if history.cursor != Some(index) {
+            restore.send(RestoreSnapshot(index));
+        }
+    }
+}
+
+/// Count simulation ticks and snapshot every `interval` of them. Paused
+/// frames do not advance virtual time and are not ticks.
+pub fn record_history(
+    time: Res<Time>,
+    mut history: ResMut<SimulationHistory>,
+    nodes: Query<(Entity, &Node, &MagneticField, &Transform)>,
+    connections: Query<&Connection>,
+) {
+    if time.delta().is_zero() {
+        return;
+    }
+    history.tick += 1;
+    if !history.due() {
+        return;
+    }
+
+    let mut indices = HashMap::new();
+    let nodes: Vec<NodeSnapshot> = nodes
+        .iter()
+        .enumerate()
+        .map(|(index, (entity, node, field, transform))| {
+            indices.insert(entity, index);
+            NodeSnapshot {
+                node: node.clone(),
+                field: *field,
+                transform: *transform,
+            }
+        })
+        .collect();
+    let connections = connections
+        .iter()
+        .filter_map(|connection| {
+            Some(ConnectionSnapshot {
+                start: *indices.get(&connection.start)?,
+                end: *indices.get(&connection.end)?,
+                direction: connection.direction,
+            })
+        })
+        .collect();
+
+    let tick = history.tick;
+    history.push(SimulationSnapshot {
+        tick,
+        elapsed: time.elapsed_secs(),
+        nodes,
+        connections,
+    });
+}
+
+/// Replace every node and connection with those of the requested snapshot
+/// and pause, so the restored frame can be inspected before resuming
+#[allow(clippy::too_many_arguments)]
+pub fn restore_snapshot(
+    mut commands: Commands,
+    mut requests: EventReader<RestoreSnapshot>,
+    mut history: ResMut<SimulationHistory>,
+    mut animation_state: ResMut<AnimationState>,
+    mut selection: ResMut<Selection>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    materials: Res<MaterialHandles>,
+    existing: Query<Entity, Or<(With<Node>, With<Connection>)>>,
+) {
+    // Only the latest request of a drag matters
+    let Some(RestoreSnapshot(index)) = requests.read().last().copied() else {
+        return;
+    };
+    let Some(snapshot) = history.rewind_to(index) else {
+        return;
+    };
+
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    selection.clear();
+
+    let entities: Vec<Entity> = snapshot
+        .nodes
+        .iter()
+        .map(|saved| {
+            let entity = spawn_node(
+                &mut commands,
+                &mut meshes,
+                &materials,
+                saved.node.shape_type,
+                saved.field,
+                saved.transform,
+            );
+            // Keep the saved phase and rotation, not a fresh node's
+            commands.entity(entity).insert(saved.node.clone());
+            entity
+        })
+        .collect();
+
+    for connection in &snapshot.connections {
+        if let (Some(start), Some(end)) = (entities.get(connection.start), entities.get(connection.end)) {
+            commands.spawn(Connection {
+                start: *start,
+                end: *end,
+                direction: connection.direction,
+            });
+        }
+    }
+
+    animation_state.paused = true;
+}
+
+/// Move the handle to the shown snapshot and describe it
+pub fn update_timeline(
+    history: Res<SimulationHistory>,
+    mut handles: Query<&mut bevy::ui::Node, With<TimelineHandle>>,
+    mut labels: Query<&mut Text, With<TimelineLabel>>,
+) {
+    if !history.is_changed() {
+        return;
+    }
+    let shown = history.cursor.unwrap_or(history.len().saturating_sub(1));
+
+    for mut handle in handles.iter_mut() {
+        handle.left = Val::Percent(history.fraction_of(shown) * 100.0);
+    }
+    for mut label in labels.iter_mut() {
+        label.0 = match history.get(shown) {
+            Some(snapshot) => format!(
+                "Snapshot {}/{}: tick {} at {:.1}s{}",
+                shown + 1,
+                history.len(),
+                snapshot.tick,
+                snapshot.elapsed,
+                if history.cursor.is_some() { " (rewound; resume to continue from here)" } else { "" },
+            ),
+            None => "No snapshots yet".to_string(),
+        };
+    }
+}
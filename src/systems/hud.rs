@@ -0,0 +1,105 @@
+use std::fmt::Write;
+use bevy::{
+    prelude::*,
+    diagnostic::{DiagnosticPath, DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+};
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    components::{Connection, Node},
+    err::{ErrorManager, RECENT_ERROR_WINDOW},
+    resources::{input_bindings::HyvoAction, uni_color::UniColor, AnimationState, ParticleBudget},
+    systems::magnetic::{InteractionPairs, PHYSICS_TICK_TIME},
+};
+
+const HUD_FONT_PATH: &str = "fonts/Roboto-Regular.ttf";
+const HUD_FONT_SIZE: f32 = 14.0;
+
+/// Statistics overlay in the top-left corner
+#[derive(Component)]
+pub struct Hud;
+
+pub fn setup_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        bevy::ui::Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(12.0),
+            top: Val::Px(12.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(UniColor::srgb(0.05, 0.05, 0.08).with_alpha(0.7).as_bevy_color()),
+        Text::new(""),
+        TextFont {
+            font: asset_server.load(HUD_FONT_PATH),
+            font_size: HUD_FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Hud,
+    ));
+}
+
+pub fn toggle_hud(
+    actions: Res<ActionState<HyvoAction>>,
+    mut huds: Query<&mut Visibility, With<Hud>>,
+) {
+    if !actions.just_pressed(&HyvoAction::ToggleHud) {
+        return;
+    }
+    for mut visibility in huds.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_hud(
+    diagnostics: Res<DiagnosticsStore>,
+    animation_state: Res<AnimationState>,
+    interactions: Res<InteractionPairs>,
+    budget: Res<ParticleBudget>,
+    error_manager: Res<ErrorManager>,
+    nodes: Query<(), With<Node>>,
+    connections: Query<(), With<Connection>>,
+    mut huds: Query<(&mut Text, &InheritedVisibility), With<Hud>>,
+) {
+    let smoothed = |path: &DiagnosticPath| diagnostics.get(path).and_then(|diagnostic| diagnostic.smoothed());
+
+    for (mut text, visibility) in huds.iter_mut() {
+        if !visibility.get() {
+            continue;
+        }
+        let text = &mut text.0;
+        text.clear();
+
+        let fps = smoothed(&FrameTimeDiagnosticsPlugin::FPS).map_or("-".to_string(), |fps| format!("{fps:.0}"));
+        let tick = smoothed(&PHYSICS_TICK_TIME).map_or("-".to_string(), |ms| format!("{ms:.2} ms"));
+        writeln!(text, "FPS: {fps}").ok();
+        writeln!(text, "Physics: {tick}").ok();
+        writeln!(text, "Nodes: {}  Connections: {}", nodes.iter().len(), connections.iter().len()).ok();
+        // GPU particle counts are not read back, so show what the budget allows
+        writeln!(
+            text,
+            "Particles: {} CPU, {} GPU capacity ({} of {} emitters culled)",
+            budget.cpu_live, budget.active_capacity, budget.culled, budget.emitters,
+        )
+        .ok();
+        writeln!(text, "Interaction energy: {:.2}", interactions.total_energy).ok();
+        writeln!(
+            text,
+            "{} at {}x",
+            if animation_state.paused { "Paused" } else { "Running" },
+            animation_state.speed,
+        )
+        .ok();
+        write!(
+            text,
+            "Errors (last {}s): {}",
+            RECENT_ERROR_WINDOW.as_secs(),
+            error_manager.recent_error_count(),
+        )
+        .ok();
+    }
+}
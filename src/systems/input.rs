@@ -0,0 +1,92 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{save_to_disk, Screenshot},
+};
+use leafwing_input_manager::prelude::*;
+use crate::{
+    resources::input_bindings::{HyvoAction, InputBindings, InputBindingsHandle, INPUT_BINDINGS_PATH},
+    err::{Error, ErrorManager, ResourceError},
+};
+
+pub fn load_input_bindings(
+    asset_server: Res<AssetServer>,
+    mut handle: ResMut<InputBindingsHandle>,
+) {
+    handle.0 = asset_server.load(INPUT_BINDINGS_PATH);
+}
+
+/// Rebuild the input map when the overrides load or are edited on disk
+pub fn apply_input_bindings(
+    mut events: EventReader<AssetEvent<InputBindings>>,
+    handle: Res<InputBindingsHandle>,
+    bindings: Res<Assets<InputBindings>>,
+    mut input_map: ResMut<InputMap<HyvoAction>>,
+    error_manager: Res<ErrorManager>,
+) {
+    let changed = events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => *id == handle.0.id(),
+        _ => false,
+    });
+    if !changed {
+        return;
+    }
+    let Some(overrides) = bindings.get(&handle.0) else {
+        return;
+    };
+
+    // The loader validates too, but assets can also be edited in place
+    if let Err(e) = overrides.validate() {
+        error_manager.report_with_recovery(e, "Keeping the previous input bindings");
+        return;
+    }
+    *input_map = overrides.input_map();
+    info!("Applied {} input binding override(s)", overrides.bindings.len());
+}
+
+/// Reports a bindings file that failed to parse, once per failure
+pub fn report_input_binding_errors(
+    asset_server: Res<AssetServer>,
+    handle: Res<InputBindingsHandle>,
+    error_manager: Res<ErrorManager>,
+    mut reported: Local<bool>,
+) {
+    let failed = matches!(
+        asset_server.get_load_state(handle.0.id()),
+        Some(bevy::asset::LoadState::Failed(_))
+    );
+    if failed && !*reported {
+        error_manager.report_error(Error::Resource(ResourceError::LoadFailed(format!(
+            "Input bindings {INPUT_BINDINGS_PATH} failed to load; using defaults"
+        ))));
+    }
+    *reported = failed;
+}
+
+/// Saves the primary window to a timestamped PNG in the working directory
+pub fn take_screenshot(
+    mut commands: Commands,
+    actions: Res<ActionState<HyvoAction>>,
+) {
+    if !actions.just_pressed(&HyvoAction::Screenshot) {
+        return;
+    }
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    let path = format!("screenshot-{stamp}.png");
+
+    info!("Saving screenshot to {path}");
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path));
+}
+
+pub fn exit_on_action(
+    actions: Res<ActionState<HyvoAction>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if actions.just_pressed(&HyvoAction::Exit) {
+        exit.send(AppExit::Success);
+    }
+}
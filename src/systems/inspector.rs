@@ -0,0 +1,168 @@
+use bevy::{
+    prelude::*,
+    reflect::TypeRegistry,
+    window::PrimaryWindow,
+};
+use bevy_inspector_egui::{
+    bevy_egui::{egui, EguiContext, EguiContexts},
+    reflect_inspector::ui_for_value,
+};
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    components::{MagneticField, Node},
+    err::ErrorManager,
+    resources::{
+        input_bindings::HyvoAction,
+        AnimationState, HelixConfig, LorentzConfig, MaterialColors, MaterialHandles, ParticleBackend,
+        RegenerateHelix, Selection, SimulationConfig,
+    },
+};
+
+/// Whether the inspector window is shown
+#[derive(Resource, Debug, Default)]
+pub struct InspectorState {
+    pub open: bool,
+}
+
+pub fn toggle_inspector(
+    actions: Res<ActionState<HyvoAction>>,
+    mut state: ResMut<InspectorState>,
+) {
+    if actions.just_pressed(&HyvoAction::ToggleInspector) {
+        state.open = !state.open;
+    }
+}
+
+/// Keep typing in a field or dragging a slider from also driving the scene.
+/// Runs after leafwing has updated `ActionState`, using the pointer and
+/// keyboard claims egui made last frame.
+pub fn release_input_captured_by_inspector(
+    state: Res<InspectorState>,
+    mut contexts: EguiContexts,
+    mut actions: ResMut<ActionState<HyvoAction>>,
+) {
+    if !state.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    if ctx.wants_keyboard_input() {
+        actions.reset_all();
+    } else if ctx.wants_pointer_input() || ctx.is_pointer_over_area() {
+        for action in [
+            HyvoAction::Select,
+            HyvoAction::OrbitDrag,
+            HyvoAction::PanDrag,
+            HyvoAction::Zoom,
+        ] {
+            actions.reset(&action);
+        }
+    }
+}
+
+/// Draw the inspector. Exclusive because the reflection UI edits resources
+/// and components by type.
+pub fn inspector_ui(world: &mut World) {
+    if !world.resource::<InspectorState>().open {
+        return;
+    }
+    let Ok(egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single(world)
+    else {
+        return;
+    };
+    let mut egui_context = egui_context.clone();
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    egui::Window::new("Inspector")
+        .default_width(320.0)
+        .show(egui_context.get_mut(), |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.collapsing("Helix", |ui| {
+                    if edit_resource::<HelixConfig>(world, ui, &type_registry) {
+                        regenerate_helix(world);
+                    }
+                });
+                ui.collapsing("Simulation", |ui| {
+                    if edit_resource::<AnimationState>(world, ui, &type_registry) {
+                        world.resource_mut::<AnimationState>().clamp_speed();
+                    }
+                    if edit_resource::<SimulationConfig>(world, ui, &type_registry) {
+                        if let Err(e) = world.resource::<SimulationConfig>().validate() {
+                            world.resource::<ErrorManager>().report_error(e);
+                        }
+                    }
+                });
+                ui.collapsing("Particles", |ui| {
+                    // Applies to emitters spawned from now on
+                    edit_resource::<ParticleBackend>(world, ui, &type_registry);
+                    edit_resource::<LorentzConfig>(world, ui, &type_registry);
+                });
+                ui.collapsing("Colours", |ui| {
+                    if edit_resource::<MaterialColors>(world, ui, &type_registry) {
+                        apply_material_colors(world);
+                    }
+                });
+                ui.collapsing("Selected node", |ui| {
+                    let Some(entity) = world.resource::<Selection>().primary() else {
+                        ui.label("Nothing selected");
+                        return;
+                    };
+                    edit_component::<Node>(world, entity, ui, &type_registry);
+                    edit_component::<MagneticField>(world, entity, ui, &type_registry);
+                });
+            });
+        });
+}
+
+/// Returns whether the resource was edited; only then is it marked changed
+fn edit_resource<R: Resource + Reflect>(world: &mut World, ui: &mut egui::Ui, type_registry: &TypeRegistry) -> bool {
+    let Some(mut resource) = world.get_resource_mut::<R>() else {
+        return false;
+    };
+    let changed = ui_for_value(resource.bypass_change_detection(), ui, type_registry);
+    if changed {
+        resource.set_changed();
+    }
+    changed
+}
+
+fn edit_component<C: Component + Reflect>(
+    world: &mut World,
+    entity: Entity,
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+) -> bool {
+    let Some(mut component) = world.get_mut::<C>(entity) else {
+        return false;
+    };
+    let changed = ui_for_value(component.bypass_change_detection(), ui, type_registry);
+    if changed {
+        component.set_changed();
+    }
+    changed
+}
+
+/// Only a valid configuration replaces the current helix
+fn regenerate_helix(world: &mut World) {
+    match world.resource::<HelixConfig>().validate() {
+        Ok(()) => {
+            world.send_event(RegenerateHelix);
+        }
+        Err(e) => {
+            world
+                .resource::<ErrorManager>()
+                .report_with_recovery(e, "Keeping the current helix until the configuration is valid");
+        }
+    }
+}
+
+fn apply_material_colors(world: &mut World) {
+    world.resource_scope(|world, mut handles: Mut<MaterialHandles>| {
+        let colors = world.resource::<MaterialColors>().clone();
+        handles.apply_colors(&colors, &mut world.resource_mut::<Assets<StandardMaterial>>());
+    });
+}
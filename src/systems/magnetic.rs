@@ -1,209 +1,110 @@
 use bevy::{
     prelude::*,
-    pbr::StandardMaterial,
+    diagnostic::{DiagnosticPath, Diagnostics},
+    utils::{HashMap, HashSet, Instant},
 };
 use bevy_hanabi::prelude::*;
 use crate::{
-    components::{MagneticField, Polarity},
-    err::{Result, SystemError},
+    components::{Dragged, InteractionEffect, MagneticField, Polarity},
+    resources::{EffectKind, EffectRegistry, ParticleBudget},
 };
-use bevy::math::Vec4;
 
-#[derive(Component)]
-pub struct NodeMaterial(pub Handle<StandardMaterial>);
-
-#[derive(Resource)]
-pub struct MagneticEffects {
-    pub north: Handle<EffectAsset>,
-    pub south: Handle<EffectAsset>,
-    pub interaction: Handle<EffectAsset>,
+/// Pairs whose distance-attenuated interaction is weaker than this get no particle stream
+pub const INTERACTION_THRESHOLD: f32 = 0.5;
+/// Interaction strength at which a stream emits at its full rate
+const FULL_INTERACTION_STRENGTH: f32 = 10.0;
+/// Fraction of a node's orientation kept per damping step
+const ORIENTATION_DAMPING: f32 = 0.95;
+/// Damping steps per second of simulation time, so the decay is frame-rate
+/// independent and stops while paused
+const DAMPING_RATE_HZ: f32 = 60.0;
+/// Wall time spent in `update_magnetic_fields`, in milliseconds
+pub const PHYSICS_TICK_TIME: DiagnosticPath = DiagnosticPath::const_new("hyvo/physics_tick_time");
+
+/// A pair of nodes with significant interaction this frame. Strength is
+/// positive for attraction and negative for repulsion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InteractionPair {
+    pub source: Entity,
+    pub target: Entity,
+    pub strength: f32,
 }
 
-pub struct MagneticProperties {
-    attraction_accel: f32,
-    max_attraction_speed: f32,
-    sticky_factor: f32,
-    shell_half_thickness: f32,
+/// Significant node interactions found by `update_magnetic_fields`, keyed by
+/// the entity pair in ascending order
+#[derive(Resource, Debug, Default)]
+pub struct InteractionPairs {
+    pairs: HashMap<(Entity, Entity), InteractionPair>,
+    /// Potential energy summed over every pair in range, not only the
+    /// significant ones; see `pair_potential`
+    pub total_energy: f32,
 }
 
-pub fn setup_magnetic_effects(
-    mut commands: Commands,
-    mut effects: ResMut<Assets<EffectAsset>>,
-) -> Result<()> {
-    // Create base gradients
-    let mut north_gradient = Gradient::new();
-    north_gradient.add_key(0.0, Vec4::new(0.0, 0.5, 1.0, 1.0));
-    north_gradient.add_key(0.5, Vec4::new(0.2, 0.6, 1.0, 0.8));
-    north_gradient.add_key(1.0, Vec4::new(0.4, 0.7, 1.0, 0.0));
-
-    let mut south_gradient = Gradient::new();
-    south_gradient.add_key(0.0, Vec4::new(1.0, 0.2, 0.0, 1.0));
-    south_gradient.add_key(0.5, Vec4::new(1.0, 0.4, 0.2, 0.8));
-    south_gradient.add_key(1.0, Vec4::new(1.0, 0.6, 0.4, 0.0));
-
-    let mut interaction_gradient = Gradient::new();
-    interaction_gradient.add_key(0.0, Vec4::new(1.0, 1.0, 1.0, 1.0));
-    interaction_gradient.add_key(0.3, Vec4::new(0.8, 0.8, 1.0, 0.8));
-    interaction_gradient.add_key(0.7, Vec4::new(0.6, 0.6, 1.0, 0.4));
-    interaction_gradient.add_key(1.0, Vec4::new(0.4, 0.4, 1.0, 0.0));
-
-    // Create the effects
-    let writer = ExprWriter::new();
-
-    // Base properties for field effects
-    let age = writer.lit(0.).expr();
-    let init_age = SetAttributeModifier::new(Attribute::AGE, age);
-    let lifetime = writer.lit(2.).expr();
-    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, lifetime);
-
-    // Force field properties
-    let attraction_accel = 20.0;
-    let max_attraction_speed = 5.0;
-    let sticky_factor = 2.0;
-    let shell_half_thickness = 0.1;
-
-    // North pole effect
-    let north_effect = create_magnetic_pole_effect(
-        &mut effects,
-        writer.clone(),
-        north_gradient,
-        init_age.clone(),
-        init_lifetime.clone(),
-        attraction_accel,
-        max_attraction_speed,
-        sticky_factor,
-        shell_half_thickness,
-        true,
-    )?;
-
-    // South pole effect
-    let south_effect = create_magnetic_pole_effect(
-        &mut effects,
-        writer.clone(),
-        south_gradient,
-        init_age.clone(),
-        init_lifetime.clone(),
-        attraction_accel,
-        max_attraction_speed,
-        sticky_factor,
-        shell_half_thickness,
-        false,
-    )?;
-
-    // Interaction effect
-    let interaction_effect = create_interaction_effect(
-        &mut effects,
-        writer,
-        interaction_gradient,
-        init_age,
-        init_lifetime,
-    )?;
-
-    commands.insert_resource(MagneticEffects {
-        north: effects.add(north_effect),
-        south: effects.add(south_effect),
-        interaction: effects.add(interaction_effect),
-    });
-
-    Ok(())
+impl InteractionPairs {
+    pub fn key(a: Entity, b: Entity) -> (Entity, Entity) {
+        if a < b { (a, b) } else { (b, a) }
+    }
+
+    pub fn get(&self, a: Entity, b: Entity) -> Option<&InteractionPair> {
+        self.pairs.get(&Self::key(a, b))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &InteractionPair> {
+        self.pairs.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn insert(&mut self, pair: InteractionPair) {
+        self.pairs.insert(Self::key(pair.source, pair.target), pair);
+    }
+
+    pub fn clear(&mut self) {
+        self.pairs.clear();
+        self.total_energy = 0.0;
+    }
 }
 
-fn create_magnetic_pole_effect(
-    _effects: &mut Assets<EffectAsset>,
-    writer: ExprWriter,
-    gradient: Gradient<Vec4>,
-    init_age: SetAttributeModifier,
-    init_lifetime: SetAttributeModifier,
+pub struct MagneticProperties {
     attraction_accel: f32,
     max_attraction_speed: f32,
     sticky_factor: f32,
     shell_half_thickness: f32,
-    is_north: bool,
-) -> Result<EffectAsset> {
-    let init_pos = SetPositionSphereModifier {
-        center: writer.lit(Vec3::ZERO).expr(),
-        radius: writer.lit(0.5).expr(),
-        dimension: ShapeDimension::Surface,
-    };
-
-    let init_vel = SetVelocitySphereModifier {
-        center: writer.lit(Vec3::ZERO).expr(),
-        speed: writer.lit(if is_north { 2.0 } else { -2.0 }).expr(),
-    };
-
-    // Force field effect
-    let force_field = ConformToSphereModifier {
-        origin: writer.lit(Vec3::ZERO).expr(),
-        radius: writer.lit(1.0).expr(),
-        influence_dist: writer.lit(5.0).expr(),
-        attraction_accel: writer.lit(attraction_accel).expr(),
-        max_attraction_speed: writer.lit(max_attraction_speed).expr(),
-        sticky_factor: Some(writer.lit(sticky_factor).expr()),
-        shell_half_thickness: Some(writer.lit(shell_half_thickness).expr()),
-    };
-
-    Ok(EffectAsset::new(4096, Spawner::rate(100.0.into()), writer.finish())
-        .with_name(if is_north { "north_pole" } else { "south_pole" })
-        .init(init_pos)
-        .init(init_vel)
-        .init(init_age)
-        .init(init_lifetime)
-        .update(force_field)
-        .render(ColorOverLifetimeModifier { gradient }))
 }
 
-fn create_interaction_effect(
-    _effects: &mut Assets<EffectAsset>,
-    writer: ExprWriter,
-    gradient: Gradient<Vec4>,
-    init_age: SetAttributeModifier,
-    init_lifetime: SetAttributeModifier,
-) -> Result<EffectAsset> {
-    let init_pos = SetPositionSphereModifier {
-        center: writer.lit(Vec3::ZERO).expr(),
-        radius: writer.lit(0.2).expr(),
-        dimension: ShapeDimension::Surface,
-    };
-
-    let init_vel = SetVelocityTangentModifier {
-        origin: writer.lit(Vec3::ZERO).expr(),
-        axis: writer.lit(Vec3::Y).expr(),
-        speed: writer.lit(3.0).expr(),
-    };
-
-    let radial_accel = RadialAccelModifier::new(
-        writer.lit(Vec3::ZERO).expr(),
-        writer.lit(-2.0).expr(),
-    );
-
-    Ok(EffectAsset::new(2048, Spawner::rate(50.0.into()), writer.finish())
-        .with_name("magnetic_interaction")
-        .init(init_pos)
-        .init(init_vel)
-        .init(init_age)
-        .init(init_lifetime)
-        .update(radial_accel)
-        .render(ColorOverLifetimeModifier { gradient }))
+/// Potential energy of a pair whose attraction follows `strength / (r² + 1)`,
+/// zero at infinite separation: the integral of that force out to infinity.
+/// Negative for attracting pairs, positive for repelling ones.
+pub fn pair_potential(strength: f32, distance: f32) -> f32 {
+    -strength * (std::f32::consts::FRAC_PI_2 - distance.atan())
 }
 
 pub fn update_magnetic_fields(
     time: Res<Time>,
-    mut query: Query<(Entity, &mut Transform, &mut MagneticField)>,
-    magnetic_effects: Option<Res<MagneticEffects>>,
-    _commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &mut MagneticField, Has<Dragged>)>,
+    mut interactions: ResMut<InteractionPairs>,
+    mut diagnostics: Diagnostics,
 ) {
+    let started = Instant::now();
     let dt = time.delta_secs();
+    interactions.clear();
 
     // First, collect all field data we need
     let field_data: Vec<(Entity, Vec3, MagneticField)> = query
         .iter()
-        .map(|(entity, transform, field)| {
+        .map(|(entity, transform, field, _)| {
             (entity, transform.translation, *field)
         })
         .collect();
 
     // Then process each field using the collected data
-    for (entity, mut transform, mut field) in query.iter_mut() {
+    for (entity, mut transform, mut field, dragged) in query.iter_mut() {
         // Update field orientation based on strength and interaction
         let orientation_change = field.strength * dt;
         field.orientation += orientation_change;
@@ -212,67 +113,184 @@ pub fn update_magnetic_fields(
         }
 
         // Apply magnetic forces
-        if let Some(_effects) = magnetic_effects.as_ref() {
-            let mut total_force = Vec3::ZERO;
-            let mut orientation_influence = 0.0;
-
-            // Calculate forces from other magnetic fields using the collected data
-            for &(other_entity, other_pos, other_field) in field_data.iter() {
-                if entity == other_entity {
-                    continue;
-                }
-
-                let direction = other_pos - transform.translation;
-                let distance = direction.length();
-
-                // Skip if too far
-                if distance > field.interaction_radius + other_field.interaction_radius {
-                    continue;
-                }
-
-                // Calculate base force
-                let force_magnitude = match field.calculate_base_interaction(&other_field) {
-                    Ok(mag) => mag,
-                    Err(_) => continue,
-                };
+        let mut total_force = Vec3::ZERO;
+        let mut orientation_influence = 0.0;
+
+        // Calculate forces from other magnetic fields using the collected data
+        for &(other_entity, other_pos, other_field) in field_data.iter() {
+            if entity == other_entity {
+                continue;
+            }
+
+            let direction = other_pos - transform.translation;
+            let distance = direction.length();
 
-                // Apply distance falloff
-                let force = direction.normalize() * force_magnitude / (distance * distance + 1.0);
-                total_force += force;
-
-                // Calculate orientation influence based on polarity interaction
-                let field_direction = Vec3::new(
-                    field.orientation.cos(),
-                    0.0,
-                    field.orientation.sin(),
-                );
-                let alignment = field_direction.dot(direction.normalize());
-                orientation_influence += alignment * force_magnitude * 0.1;
+            // Skip if too far
+            if distance > field.interaction_radius + other_field.interaction_radius {
+                continue;
             }
 
-            // Apply accumulated forces with strength-based mobility
+            // Calculate base force
+            let force_magnitude = match field.calculate_base_interaction(&other_field) {
+                Ok(mag) => mag,
+                Err(_) => continue,
+            };
+
+            // Apply distance falloff
+            let attenuated = force_magnitude / (distance * distance + 1.0);
+            let force = direction.normalize() * attenuated;
+            total_force += force;
+
+            if entity < other_entity {
+                interactions.total_energy += pair_potential(force_magnitude, distance);
+            }
+
+            // Each pair is visited from both ends; record it once. Attraction
+            // streams flow from the north node to the south node.
+            if entity < other_entity && attenuated.abs() >= INTERACTION_THRESHOLD {
+                let (source, target) = if field.polarity == Polarity::South && attenuated > 0.0 {
+                    (other_entity, entity)
+                } else {
+                    (entity, other_entity)
+                };
+                interactions.insert(InteractionPair { source, target, strength: attenuated });
+            }
+
+            // Calculate orientation influence based on polarity interaction
+            let field_direction = Vec3::new(
+                field.orientation.cos(),
+                0.0,
+                field.orientation.sin(),
+            );
+            let alignment = field_direction.dot(direction.normalize());
+            orientation_influence += alignment * force_magnitude * 0.1;
+        }
+
+        // Apply accumulated forces with strength-based mobility; a node held
+        // by the pointer still exerts force on others but is not moved itself
+        if !dragged {
             let movement = total_force * dt * field.strength;
             transform.translation += movement;
+        }
+
+        // Apply orientation influence with damping
+        field.orientation += orientation_influence * dt;
+        field.orientation *= ORIENTATION_DAMPING.powf(dt * DAMPING_RATE_HZ);
+    }
 
-            // Apply orientation influence with damping
-            field.orientation += orientation_influence * dt;
-            field.orientation *= 0.95; // Damping
+    diagnostics.add_measurement(&PHYSICS_TICK_TIME, || started.elapsed().as_secs_f64() * 1000.0);
+}
+
+/// Spawn, update and despawn interaction streams to match `InteractionPairs`
+pub fn update_interaction_effects(
+    mut commands: Commands,
+    interactions: Res<InteractionPairs>,
+    budget: Res<ParticleBudget>,
+    registry: Res<EffectRegistry>,
+    effects: Res<Assets<EffectAsset>>,
+    nodes: Query<&GlobalTransform, With<MagneticField>>,
+    mut streams: Query<(Entity, &mut InteractionEffect, &mut Transform, &mut EffectProperties, &mut ParticleEffect)>,
+) {
+    if !registry.contains(EffectKind::Interaction) {
+        return;
+    }
+    let handle = registry.resolve(EffectKind::Interaction, &effects);
+    let mut covered = HashSet::new();
+
+    for (entity, mut stream, mut transform, mut properties, mut effect) in streams.iter_mut() {
+        let pair = interactions.get(stream.source, stream.target).copied();
+        let positions = pair.and_then(|pair| Some((nodes.get(pair.source).ok()?, nodes.get(pair.target).ok()?)));
+
+        let (Some(pair), Some((source, target))) = (pair, positions) else {
+            // The pair separated or one of its nodes is gone
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        covered.insert(InteractionPairs::key(pair.source, pair.target));
+        stream.source = pair.source;
+        stream.target = pair.target;
+        stream.strength = pair.strength;
+        transform.translation = source.translation();
+        // Streams spawned on the fallback switch over once the definition loads
+        if effect.handle != handle {
+            effect.handle = handle.clone();
+        }
+        set_interaction_properties(
+            &mut properties,
+            pair.strength * budget.spawn_scale,
+            target.translation() - source.translation(),
+        );
+    }
+
+    for pair in interactions.iter() {
+        if covered.contains(&InteractionPairs::key(pair.source, pair.target)) {
+            continue;
         }
+        let (Ok(source), Ok(target)) = (nodes.get(pair.source), nodes.get(pair.target)) else {
+            continue;
+        };
+
+        let mut properties = EffectProperties::default();
+        set_interaction_properties(
+            &mut properties,
+            pair.strength * budget.spawn_scale,
+            target.translation() - source.translation(),
+        );
+
+        commands.spawn((
+            ParticleEffect::new(handle.clone()),
+            properties,
+            Transform::from_translation(source.translation()),
+            InteractionEffect {
+                source: pair.source,
+                target: pair.target,
+                strength: pair.strength,
+            },
+        ));
     }
 }
 
-/// Calculate the final interaction strength between two magnetic fields considering distance
-fn calculate_interaction_strength(
-    strength_a: &f32,
-    strength_b: &f32,
-    polarity_a: &Polarity,
-    polarity_b: &Polarity,
-    distance: f32,
-    radius: &f32,
-) -> f32 {
-    let base_strength = strength_a * strength_b * (1.0 - distance / radius);
-    match (polarity_a, polarity_b) {
-        (Polarity::North, Polarity::South) | (Polarity::South, Polarity::North) => base_strength,
-        _ => -base_strength,
+fn set_interaction_properties(properties: &mut EffectProperties, strength: f32, offset: Vec3) {
+    let intensity = (strength.abs() / FULL_INTERACTION_STRENGTH).clamp(0.0, 1.0);
+    let attract = if strength > 0.0 { 1.0 } else { 0.0 };
+
+    properties.set("target", offset.into());
+    properties.set("attract", attract.into());
+    properties.set("intensity", intensity.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interaction_pairs_follow_node_separation() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<InteractionPairs>();
+        app.init_resource::<bevy::diagnostic::DiagnosticsStore>();
+        app.add_systems(Update, update_magnetic_fields);
+
+        let south = app.world_mut().spawn((
+            Transform::from_xyz(1.0, 0.0, 0.0),
+            MagneticField { polarity: Polarity::South, strength: 2.0, ..default() },
+        )).id();
+        let north = app.world_mut().spawn((
+            Transform::default(),
+            MagneticField { polarity: Polarity::North, strength: 2.0, ..default() },
+        )).id();
+
+        app.update();
+        let pair = *app.world().resource::<InteractionPairs>().get(south, north).unwrap();
+        assert!(pair.strength > 0.0);
+        assert_eq!((pair.source, pair.target), (north, south));
+        // An attracting pair sits in a potential well
+        assert!(app.world().resource::<InteractionPairs>().total_energy < 0.0);
+
+        app.world_mut().entity_mut(south).insert(Transform::from_xyz(100.0, 0.0, 0.0));
+        app.update();
+        assert!(app.world().resource::<InteractionPairs>().is_empty());
+        assert_eq!(app.world().resource::<InteractionPairs>().total_energy, 0.0);
     }
-} 
\ No newline at end of file
+}
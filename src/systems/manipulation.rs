@@ -0,0 +1,211 @@
+use bevy::{
+    prelude::*,
+    math::primitives::InfinitePlane3d,
+    picking::{
+        events::{Drag, DragEnd, DragStart, Pointer},
+        mesh_picking::ray_cast::{MeshRayCast, RayCastSettings},
+        pointer::PointerButton,
+    },
+    utils::HashSet,
+    window::PrimaryWindow,
+};
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    components::{
+        particle_emitter::{EmitterShape, ParticleEmitter},
+        Dragged, Hovered, MagneticField, MainCamera, Node, Polarity,
+    },
+    resources::{input_bindings::HyvoAction, HelixConfig, MaterialHandles, NodeSpawnSettings, Selection},
+    systems::{
+        generation::{node_shape_mesh, spawn_node},
+        particles::SpawnEmitter,
+        selection::SelectedNodes,
+    },
+    err::{Error, ErrorManager, SystemError},
+};
+
+/// Distance along the cursor ray used when spawning into empty space
+const EMPTY_SPACE_SPAWN_DISTANCE: f32 = 10.0;
+/// Surface points sampled for the burst that announces a spawned node
+const SPAWN_BURST_POINTS: usize = 256;
+/// Seconds the spawn burst emits for
+const SPAWN_BURST_LIFETIME: f32 = 0.5;
+
+/// Drag nodes in the plane through the node that faces the camera
+pub fn drag_nodes(
+    mut commands: Commands,
+    mut drag_start_events: EventReader<Pointer<DragStart>>,
+    mut drag_events: EventReader<Pointer<Drag>>,
+    mut drag_end_events: EventReader<Pointer<DragEnd>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut nodes: Query<(&mut Transform, Option<&Dragged>), With<Node>>,
+) {
+    for event in drag_start_events.read() {
+        if event.button != PointerButton::Primary {
+            continue;
+        }
+        let Ok((transform, _)) = nodes.get(event.target) else {
+            continue;
+        };
+        let grabbed = event.hit.position.unwrap_or(transform.translation);
+        commands.entity(event.target).try_insert(Dragged {
+            offset: transform.translation - grabbed,
+        });
+    }
+
+    if let Ok((camera, camera_transform)) = cameras.get_single() {
+        let plane = InfinitePlane3d::new(camera_transform.forward());
+
+        for event in drag_events.read() {
+            if event.button != PointerButton::Primary {
+                continue;
+            }
+            let Ok((mut transform, Some(dragged))) = nodes.get_mut(event.target) else {
+                continue;
+            };
+            let Ok(ray) = camera.viewport_to_world(camera_transform, event.pointer_location.position) else {
+                continue;
+            };
+
+            let plane_origin = transform.translation - dragged.offset;
+            if let Some(distance) = ray.intersect_plane(plane_origin, plane) {
+                transform.translation = ray.get_point(distance) + dragged.offset;
+            }
+        }
+    }
+
+    for event in drag_end_events.read() {
+        if nodes.contains(event.target) {
+            commands.entity(event.target).remove::<Dragged>();
+        }
+    }
+}
+
+/// Cycles the shape and polarity used for newly spawned nodes
+pub fn cycle_spawn_settings(
+    actions: Res<ActionState<HyvoAction>>,
+    mut settings: ResMut<NodeSpawnSettings>,
+) {
+    if actions.just_pressed(&HyvoAction::CycleSpawnShape) {
+        settings.cycle_shape();
+        info!("Spawn shape: {:?}", settings.shape_type);
+    }
+    if actions.just_pressed(&HyvoAction::CycleSpawnPolarity) {
+        settings.polarity = settings.polarity.flipped();
+        info!("Spawn polarity: {:?}", settings.polarity);
+    }
+}
+
+/// Spawns a node where the cursor ray hits a mesh, or in front of the camera
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_node_at_cursor(
+    mut commands: Commands,
+    actions: Res<ActionState<HyvoAction>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut ray_cast: MeshRayCast,
+    mut meshes: ResMut<Assets<Mesh>>,
+    materials: Res<MaterialHandles>,
+    settings: Res<NodeSpawnSettings>,
+    config: Res<HelixConfig>,
+    error_manager: Res<ErrorManager>,
+    mut emitters: EventWriter<SpawnEmitter>,
+) {
+    if !actions.just_pressed(&HyvoAction::SpawnNode) {
+        return;
+    }
+
+    let Some(cursor) = windows.get_single().ok().and_then(|window| window.cursor_position()) else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        error_manager.report_error(Error::System(SystemError::ExecutionFailed(
+            "Cannot spawn node without a main camera".into()
+        )));
+        return;
+    };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+
+    let position = ray_cast
+        .cast_ray(ray, &RayCastSettings::default())
+        .first()
+        .map(|(_, hit)| hit.point + hit.normal.normalize_or_zero() * config.node_radius)
+        .unwrap_or_else(|| ray.get_point(EMPTY_SPACE_SPAWN_DISTANCE));
+
+    let field = MagneticField {
+        strength: config.magnetic_field_strength,
+        polarity: settings.polarity,
+        interaction_radius: config.interaction_radius,
+        particle_emission_rate: config.particle_emission_rate,
+        ..default()
+    };
+
+    spawn_node(
+        &mut commands,
+        &mut meshes,
+        &materials,
+        settings.shape_type,
+        field,
+        Transform::from_translation(position),
+    );
+
+    // Announce the node with a burst off its own surface
+    let mesh = node_shape_mesh(settings.shape_type);
+    match EmitterShape::mesh_surface(&mesh, SPAWN_BURST_POINTS, &mut rand::thread_rng()) {
+        Ok(shape) => {
+            emitters.send(SpawnEmitter {
+                position,
+                emitter: ParticleEmitter::new(shape, SPAWN_BURST_LIFETIME, settings.polarity == Polarity::North),
+            });
+        }
+        Err(e) => error_manager.report_error(e),
+    }
+}
+
+/// Removes the selected nodes and every connection touching them;
+/// `sync_selection` drops them from the selection once they are gone
+pub fn delete_selected_nodes(
+    mut commands: Commands,
+    actions: Res<ActionState<HyvoAction>>,
+    selected: SelectedNodes,
+) {
+    if !actions.just_pressed(&HyvoAction::DeleteSelected) {
+        return;
+    }
+
+    // A connection between two selected nodes is listed by both
+    let mut doomed = HashSet::new();
+    for node in selected.iter() {
+        doomed.extend(node.connections);
+        doomed.insert(node.entity);
+    }
+    for entity in doomed {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Flips the polarity of the selected nodes, or the hovered node if nothing is selected
+pub fn toggle_node_polarity(
+    actions: Res<ActionState<HyvoAction>>,
+    selection: Res<Selection>,
+    mut nodes: Query<(Entity, &mut Node, Option<&mut MagneticField>, Has<Hovered>)>,
+) {
+    if !actions.just_pressed(&HyvoAction::FlipPolarity) {
+        return;
+    }
+
+    for (entity, mut node, field, hovered) in nodes.iter_mut() {
+        let targeted = if selection.is_empty() { hovered } else { selection.contains(entity) };
+        if !targeted {
+            continue;
+        }
+
+        let polarity = node.magnetic_field.polarity.flipped();
+        node.magnetic_field.polarity = polarity;
+        if let Some(mut field) = field {
+            field.polarity = polarity;
+        }
+    }
+}
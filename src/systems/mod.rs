@@ -1,23 +1,66 @@
 // System modules
+pub mod budget;
 pub mod camera;
+pub mod cpu_particles;
+pub mod effect_definitions;
+pub mod field_visualization;
 pub mod generation;
+pub mod history;
+pub mod hud;
+pub mod input;
+pub mod inspector;
 pub mod intersections;
 pub mod magnetic;
+pub mod manipulation;
 pub mod mesh_generator;
 pub mod node_visuals;
+pub mod outline;
+pub mod particle_instancing;
 pub mod particles;
 pub mod rendering;
+pub mod selection;
 pub mod setup;
+pub mod simulation;
+pub mod theme;
+pub mod trails;
+pub mod viewports;
 
 // Re-exports for commonly used functionality
 pub use self::{
-    camera::{camera_controls, camera_setup},
-    generation::generate_helix,
+    budget::update_particle_budget,
+    camera::{
+        apply_orbit_camera, camera_bookmarks, control_camera_path, frame_nodes,
+        load_camera_paths, orbit_camera_input, play_camera_path, OrbitCamera,
+    },
+    cpu_particles::{
+        assign_cpu_backend, integrate_cpu_particles, render_cpu_particles,
+        setup_cpu_particle_renderer, spawn_cpu_particles, CpuParticleStats,
+    },
+    effect_definitions::{apply_effect_definitions, load_effect_definitions, report_effect_definition_errors},
+    field_visualization::{
+        control_field_visualization, draw_field_glyphs, drag_field_slice, setup_field_slice, update_field_slice,
+    },
+    generation::{generate_helix, spawn_node},
+    hud::{setup_hud, toggle_hud, update_hud, Hud},
+    history::{record_history, restore_snapshot, scrub_timeline, setup_timeline, toggle_timeline, update_timeline},
+    inspector::{inspector_ui, release_input_captured_by_inspector, toggle_inspector, InspectorState},
+    input::{apply_input_bindings, exit_on_action, load_input_bindings, report_input_binding_errors, take_screenshot},
     intersections::{check_intersections, setup_intersection_effects},
-    magnetic::{setup_magnetic_effects, update_magnetic_fields},
+    magnetic::{update_interaction_effects, update_magnetic_fields, InteractionPairs, PHYSICS_TICK_TIME},
+    manipulation::{cycle_spawn_settings, delete_selected_nodes, drag_nodes, spawn_node_at_cursor, toggle_node_polarity},
     mesh_generator::create_tridecahedron,
-    node_visuals::{setup_node_effects, update_node_visuals},
-    particles::{update_particles, setup_particle_system},
+    node_visuals::{
+        attach_node_effects, pulse_selected_nodes, setup_node_effects, update_node_effects, update_node_visuals,
+        EffectPool,
+    },
+    outline::{track_node_hover, update_node_outlines},
+    particle_instancing::{ParticleInstance, ParticleInstances, ParticleInstancingPlugin},
+    particles::{spawn_requested_emitters, update_particles, setup_particle_system, SpawnEmitter},
     rendering::update_rendering_visuals,
+    selection::{setup_selection_box, sync_selection, update_selection, SelectedNodes},
     setup::{setup_camera, setup_materials, setup_scene, setup_window_border, animate_window_border},
+    simulation::{control_simulation, request_initial_helix, sync_simulation_clock},
+    theme::{apply_theme, cycle_theme, load_themes, report_theme_errors},
+    trails::{attach_motion_trails, draw_motion_trails, record_motion_trails, toggle_motion_trails},
+    viewports::{setup_ortho_views, toggle_viewport_split, update_viewports, zoom_ortho_views, OrthoView},
 }; 
\ No newline at end of file
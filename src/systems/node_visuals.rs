@@ -2,141 +2,126 @@ use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 use crate::{
     components::{MagneticField, Polarity, MeshMaterial3d},
-    resources::{Materials, uni_color::UniColor},
-    err::{Result, SystemError},
+    resources::{EffectKind, EffectRegistry, MaterialColors, MaterialHandles, ParticleBudget, SelectionChanged},
 };
-use bevy::math::Vec4;
 
-#[derive(Resource)]
-pub struct NodeEffects {
-    pub active: Handle<EffectAsset>,
-    pub highlight: Handle<EffectAsset>,
-    pub pulse: Handle<EffectAsset>,
+/// Number of pre-spawned burst effects shared by all nodes
+pub const PULSE_POOL_CAPACITY: usize = 16;
+/// How long a burst effect stays checked out before returning to the pool
+const PULSE_DURATION: f32 = 1.5;
+
+/// Links a node to its persistent effect child
+#[derive(Component, Debug, Clone, Copy)]
+pub struct NodeEffect {
+    pub effect: Entity,
+    /// Cleared after a pulse fires and re-armed once the node calms down
+    pub pulse_armed: bool,
+    /// Whether the child currently runs the highlight effect
+    pub highlighted: bool,
 }
 
-pub fn setup_node_effects(
-    mut commands: Commands,
-    mut effects: ResMut<Assets<EffectAsset>>,
-) -> Result<()> {
-    let writer = ExprWriter::new();
-
-    // Base properties
-    let age = writer.lit(0.).expr();
-    let init_age = SetAttributeModifier::new(Attribute::AGE, age);
-    let lifetime = writer.lit(1.5).expr();
-    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, lifetime);
-
-    // Active node effect (constant gentle particle emission)
-    let mut active_gradient = Gradient::new();
-    active_gradient.add_key(0.0, Vec4::new(0.5, 0.8, 1.0, 0.8));
-    active_gradient.add_key(0.5, Vec4::new(0.4, 0.6, 0.9, 0.4));
-    active_gradient.add_key(1.0, Vec4::new(0.3, 0.4, 0.8, 0.0));
-
-    let active_effect = create_node_effect(
-        &mut effects,
-        writer.clone(),
-        active_gradient,
-        init_age.clone(),
-        init_lifetime.clone(),
-        30.0,
-        0.3,
-    )?;
-
-    // Highlight effect (more intense, faster particles)
-    let mut highlight_gradient = Gradient::new();
-    highlight_gradient.add_key(0.0, Vec4::new(1.0, 0.9, 0.3, 1.0));
-    highlight_gradient.add_key(0.3, Vec4::new(0.9, 0.8, 0.2, 0.8));
-    highlight_gradient.add_key(0.7, Vec4::new(0.8, 0.7, 0.1, 0.4));
-    highlight_gradient.add_key(1.0, Vec4::new(0.7, 0.6, 0.0, 0.0));
-
-    let highlight_effect = create_node_effect(
-        &mut effects,
-        writer.clone(),
-        highlight_gradient,
-        init_age.clone(),
-        init_lifetime.clone(),
-        50.0,
-        0.5,
-    )?;
-
-    // Pulse effect (bursts of particles)
-    let mut pulse_gradient = Gradient::new();
-    pulse_gradient.add_key(0.0, Vec4::new(0.2, 0.5, 1.0, 1.0));
-    pulse_gradient.add_key(0.2, Vec4::new(0.3, 0.6, 1.0, 0.9));
-    pulse_gradient.add_key(0.8, Vec4::new(0.4, 0.7, 1.0, 0.3));
-    pulse_gradient.add_key(1.0, Vec4::new(0.5, 0.8, 1.0, 0.0));
-
-    let pulse_effect = create_node_effect(
-        &mut effects,
-        writer,
-        pulse_gradient,
-        init_age,
-        init_lifetime,
-        100.0,
-        0.8,
-    )?;
-
-    commands.insert_resource(NodeEffects {
-        active: effects.add(active_effect),
-        highlight: effects.add(highlight_effect),
-        pulse: effects.add(pulse_effect),
-    });
-
-    Ok(())
+/// Marks the effect entity owned by a node
+#[derive(Component)]
+pub struct NodeEffectChild;
+
+/// Marks a pre-spawned burst effect owned by the `EffectPool`
+#[derive(Component)]
+pub struct PooledEffect;
+
+/// Fixed set of burst effects reused instead of spawning new entities
+#[derive(Resource, Debug, Default)]
+pub struct EffectPool {
+    free: Vec<Entity>,
+    busy: Vec<(Entity, f32)>,
 }
 
-fn create_node_effect(
-    _effects: &mut Assets<EffectAsset>,
-    writer: ExprWriter,
-    gradient: Gradient<Vec4>,
-    init_age: SetAttributeModifier,
-    init_lifetime: SetAttributeModifier,
-    spawn_rate: f32,
-    radius: f32,
-) -> Result<EffectAsset> {
-    let init_pos = SetPositionSphereModifier {
-        center: writer.lit(Vec3::ZERO).expr(),
-        radius: writer.lit(radius).expr(),
-        dimension: ShapeDimension::Surface,
-    };
+impl EffectPool {
+    pub fn new(entities: impl IntoIterator<Item = Entity>) -> Self {
+        Self {
+            free: entities.into_iter().collect(),
+            busy: Vec::new(),
+        }
+    }
 
-    let init_vel = SetVelocitySphereModifier {
-        center: writer.lit(Vec3::ZERO).expr(),
-        speed: writer.lit(1.0).expr(),
-    };
+    pub fn capacity(&self) -> usize {
+        self.free.len() + self.busy.len()
+    }
+
+    pub fn in_use(&self) -> usize {
+        self.busy.len()
+    }
 
-    let size_gradient = Gradient::constant(Vec3::splat(0.1));
+    /// Check out an effect for `duration` seconds. When every effect is busy the
+    /// oldest one is recycled, so bursts never allocate new entities.
+    pub fn acquire(&mut self, duration: f32) -> Option<Entity> {
+        let entity = match self.free.pop() {
+            Some(entity) => entity,
+            None if !self.busy.is_empty() => self.busy.remove(0).0,
+            None => return None,
+        };
+        self.busy.push((entity, duration));
+        Some(entity)
+    }
 
-    Ok(EffectAsset::new(1024, Spawner::rate(spawn_rate.into()), writer.finish())
-        .init(init_pos)
-        .init(init_vel)
-        .init(init_age)
-        .init(init_lifetime)
-        .render(SizeOverLifetimeModifier {
-            gradient: size_gradient,
-            screen_space_size: false,
+    /// Advance the busy timers and return expired effects to the free list
+    pub fn tick(&mut self, dt: f32) {
+        let mut index = 0;
+        while index < self.busy.len() {
+            self.busy[index].1 -= dt;
+            if self.busy[index].1 <= 0.0 {
+                let (entity, _) = self.busy.remove(index);
+                self.free.push(entity);
+            } else {
+                index += 1;
+            }
+        }
+    }
+}
+
+/// Pre-spawn the pulse pool. The pulse effect itself comes from its definition.
+pub fn setup_node_effects(
+    mut commands: Commands,
+    effects: Res<Assets<EffectAsset>>,
+    mut registry: ResMut<EffectRegistry>,
+) {
+    let pulse = registry.get_or_reserve(EffectKind::NodePulse, &effects);
+
+    let pool: Vec<Entity> = (0..PULSE_POOL_CAPACITY)
+        .map(|_| {
+            commands
+                .spawn((
+                    ParticleEffect::new(pulse.clone()),
+                    Transform::default(),
+                    PooledEffect,
+                ))
+                .id()
         })
-        .render(ColorOverLifetimeModifier { gradient }))
+        .collect();
+
+    commands.insert_resource(EffectPool::new(pool));
+}
+
+/// Field intensity normalised to 0..1 and the animated pulse intensity at `time_factor`
+pub fn node_intensity(field: &MagneticField, time_factor: f32) -> (f32, f32) {
+    let base_pulse = (time_factor * 2.0 + field.strength).sin() * 0.5 + 0.5;
+    let field_pulse = (time_factor * 4.0 + field.orientation).cos() * 0.5 + 0.5;
+    let combined_intensity = (base_pulse + field_pulse) * 0.5;
+
+    let field_intensity = (field.strength / 10.0).min(1.0); // Normalize field strength
+
+    (field_intensity, combined_intensity)
 }
 
 pub fn update_node_visuals(
     time: Res<Time>,
-    animation_state: Res<crate::AnimationState>,
     materials: Res<MaterialHandles>,
-    node_effects: Option<Res<NodeEffects>>,
-    mut commands: Commands,
-    mut query: Query<(Entity, &Transform, &MagneticField, &mut MeshMaterial3d)>,
+    mut query: Query<(&MagneticField, &mut MeshMaterial3d)>,
 ) {
-    let time_factor = time.elapsed_secs() * animation_state.speed;
-
-    for (_entity, transform, field, mut material) in query.iter_mut() {
-        // Calculate pulsing effect based on field strength and polarity
-        let base_pulse = (time_factor * 2.0 + field.strength).sin() * 0.5 + 0.5;
-        let field_pulse = (time_factor * 4.0 + field.orientation).cos() * 0.5 + 0.5;
-        let combined_intensity = (base_pulse + field_pulse) * 0.5;
-        
-        let field_intensity = (field.strength / 10.0).min(1.0); // Normalize field strength
-        
+    let time_factor = time.elapsed_secs();
+
+    for (field, mut material) in query.iter_mut() {
+        let (field_intensity, combined_intensity) = node_intensity(field, time_factor);
+
         // Update material
         material.0 = match field.polarity {
             Polarity::North => {
@@ -158,33 +143,146 @@ pub fn update_node_visuals(
                 }
             }
         };
+    }
+}
 
-        // Spawn particle effects based on node state
-        if let Some(ref effects) = node_effects {
-            // Always spawn base active effect
-            commands.spawn(ParticleEffectBundle {
-                effect: ParticleEffect::new(effects.active.clone()),
-                transform: Transform::from_translation(transform.translation),
-                ..default()
-            });
-
-            // Spawn highlight effect when node is highly active
-            if field_intensity > 0.7 {
-                commands.spawn(ParticleEffectBundle {
-                    effect: ParticleEffect::new(effects.highlight.clone()),
-                    transform: Transform::from_translation(transform.translation),
-                    ..default()
-                });
-            }
+/// Give every node exactly one effect child; it is despawned with the node
+pub fn attach_node_effects(
+    mut commands: Commands,
+    registry: Res<EffectRegistry>,
+    effects: Res<Assets<EffectAsset>>,
+    nodes: Query<Entity, (With<MagneticField>, Without<NodeEffect>, Without<ParticleEffect>)>,
+) {
+    if !registry.contains(EffectKind::NodeActive) {
+        return;
+    }
+    let handle = registry.resolve(EffectKind::NodeActive, &effects);
+
+    for entity in nodes.iter() {
+        let effect = commands
+            .spawn((
+                ParticleEffect::new(handle.clone()),
+                EffectProperties::default(),
+                Transform::default(),
+                NodeEffectChild,
+            ))
+            .set_parent(entity)
+            .id();
+
+        commands.entity(entity).try_insert(NodeEffect {
+            effect,
+            pulse_armed: true,
+            highlighted: false,
+        });
+    }
+}
+
+/// Drive each node's effect properties from its field and fire pooled pulses
+#[allow(clippy::too_many_arguments)]
+pub fn update_node_effects(
+    time: Res<Time>,
+    colors: Res<MaterialColors>,
+    budget: Res<ParticleBudget>,
+    registry: Res<EffectRegistry>,
+    effects: Res<Assets<EffectAsset>>,
+    pool: Option<ResMut<EffectPool>>,
+    mut nodes: Query<(&MagneticField, &GlobalTransform, &mut NodeEffect)>,
+    mut children: Query<(&mut EffectProperties, &mut ParticleEffect), With<NodeEffectChild>>,
+    mut pooled: Query<(&mut Transform, Option<&mut EffectSpawner>), With<PooledEffect>>,
+) {
+    let Some(mut pool) = pool else {
+        return;
+    };
+    let time_factor = time.elapsed_secs();
+    pool.tick(time.delta_secs());
+
+    for (field, transform, mut node_effect) in nodes.iter_mut() {
+        let (field_intensity, combined_intensity) = node_intensity(field, time_factor);
+        let highlighted = field_intensity > 0.7;
 
-            // Spawn pulse effect on significant state changes
-            if combined_intensity > 0.9 {
-                commands.spawn(ParticleEffectBundle {
-                    effect: ParticleEffect::new(effects.pulse.clone()),
-                    transform: Transform::from_translation(transform.translation),
-                    ..default()
-                });
+        if let Ok((mut props, mut effect)) = children.get_mut(node_effect.effect) {
+            // Only swap handles on a change; a new handle respawns the instance.
+            // Resolving each frame also replaces the fallback once a definition loads.
+            node_effect.highlighted = highlighted;
+            let kind = if highlighted { EffectKind::NodeHighlight } else { EffectKind::NodeActive };
+            let handle = registry.resolve(kind, &effects);
+            if effect.handle != handle {
+                effect.handle = handle;
             }
+
+            let color = if highlighted { colors.highlight } else { colors.polarity(field.polarity) };
+            let spawn_fraction = ((0.3 + 0.7 * field_intensity * combined_intensity) * budget.spawn_scale).clamp(0.0, 1.0);
+            let size = if highlighted { 0.15 } else { 0.1 };
+
+            props.set("spawn_fraction", spawn_fraction.into());
+            props.set("color", color.as_vec4().into());
+            props.set("size", size.into());
+        }
+
+        // Fire once per crossing of the pulse threshold rather than every frame above it
+        if combined_intensity > 0.9 && node_effect.pulse_armed {
+            node_effect.pulse_armed = false;
+            fire_pulse(&mut pool, &mut pooled, transform.translation());
+        } else if combined_intensity < 0.7 {
+            node_effect.pulse_armed = true;
         }
     }
-} 
\ No newline at end of file
+}
+
+/// Newly selected nodes acknowledge the click with a pooled pulse
+pub fn pulse_selected_nodes(
+    mut changes: EventReader<SelectionChanged>,
+    pool: Option<ResMut<EffectPool>>,
+    nodes: Query<&GlobalTransform, With<NodeEffect>>,
+    mut pooled: Query<(&mut Transform, Option<&mut EffectSpawner>), With<PooledEffect>>,
+) {
+    let Some(mut pool) = pool else {
+        return;
+    };
+    for change in changes.read() {
+        for transform in change.added.iter().filter_map(|entity| nodes.get(*entity).ok()) {
+            fire_pulse(&mut pool, &mut pooled, transform.translation());
+        }
+    }
+}
+
+/// Move a pooled burst to `position` and restart it
+fn fire_pulse(
+    pool: &mut EffectPool,
+    pooled: &mut Query<(&mut Transform, Option<&mut EffectSpawner>), With<PooledEffect>>,
+    position: Vec3,
+) {
+    let Some(entity) = pool.acquire(PULSE_DURATION) else {
+        return;
+    };
+    if let Ok((mut pulse_transform, spawner)) = pooled.get_mut(entity) {
+        pulse_transform.translation = position;
+        if let Some(mut spawner) = spawner {
+            spawner.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effect_pool_recycles_without_growing() {
+        let mut pool = EffectPool::new((0..2).map(Entity::from_raw));
+
+        let a = pool.acquire(1.0).unwrap();
+        let b = pool.acquire(2.0).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(pool.in_use(), 2);
+
+        // Exhausted: the oldest burst is recycled
+        assert_eq!(pool.acquire(1.0), Some(a));
+        assert_eq!(pool.capacity(), 2);
+
+        pool.tick(1.5);
+        assert_eq!(pool.in_use(), 1);
+        assert_eq!(pool.acquire(1.0), Some(a));
+        assert_eq!(EffectPool::default().acquire(1.0), None);
+    }
+}
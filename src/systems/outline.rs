@@ -0,0 +1,112 @@
+use bevy::{
+    prelude::*,
+    picking::events::{Out, Over, Pointer},
+};
+use bevy_mod_outline::OutlineVolume;
+use crate::{
+    components::{Hovered, MagneticField, Node, Selected},
+    resources::{MaterialColors, uni_color::UniColor},
+};
+
+/// Outline width in pixels for a hovered node
+pub const HOVER_OUTLINE_WIDTH: f32 = 2.0;
+/// Outline width in pixels for a selected node
+pub const SELECTED_OUTLINE_WIDTH: f32 = 4.0;
+/// Fraction of the base width the outline pulses by at full field strength
+const PULSE_AMPLITUDE: f32 = 0.5;
+/// Pulse frequency in radians per second
+const PULSE_FREQUENCY: f32 = 4.0;
+
+pub fn track_node_hover(
+    mut commands: Commands,
+    mut over_events: EventReader<Pointer<Over>>,
+    mut out_events: EventReader<Pointer<Out>>,
+    nodes: Query<(), With<Node>>,
+) {
+    for event in out_events.read() {
+        if nodes.contains(event.target) {
+            commands.entity(event.target).remove::<Hovered>();
+        }
+    }
+
+    for event in over_events.read() {
+        if nodes.contains(event.target) {
+            commands.entity(event.target).try_insert(Hovered);
+        }
+    }
+}
+
+/// Outline colour follows polarity and width pulses with field strength
+pub fn update_node_outlines(
+    mut commands: Commands,
+    time: Res<Time>,
+    colors: Res<MaterialColors>,
+    mut query: Query<(
+        Entity,
+        &Node,
+        Option<&MagneticField>,
+        Has<Hovered>,
+        Has<Selected>,
+        Option<&mut OutlineVolume>,
+    )>,
+) {
+    let elapsed = time.elapsed_secs();
+
+    for (entity, node, field, hovered, selected, outline) in query.iter_mut() {
+        let visible = hovered || selected;
+
+        if !visible {
+            if let Some(mut outline) = outline {
+                if outline.visible {
+                    outline.visible = false;
+                }
+            }
+            continue;
+        }
+
+        let field = field.unwrap_or(&node.magnetic_field);
+        let base_color = colors.polarity(field.polarity);
+        let colour = if selected {
+            base_color
+        } else {
+            base_color.lerp(UniColor::white(), 0.5)
+        };
+        let width = outline_width(field.strength, elapsed, selected);
+
+        match outline {
+            Some(mut outline) => {
+                outline.visible = true;
+                outline.width = width;
+                outline.colour = colour.as_bevy_color();
+            }
+            None => {
+                commands.entity(entity).try_insert(OutlineVolume {
+                    visible: true,
+                    width,
+                    colour: colour.as_bevy_color(),
+                });
+            }
+        }
+    }
+}
+
+/// Outline width for a node of the given field strength at time `elapsed`
+pub fn outline_width(strength: f32, elapsed: f32, selected: bool) -> f32 {
+    let base = if selected { SELECTED_OUTLINE_WIDTH } else { HOVER_OUTLINE_WIDTH };
+    let intensity = (strength / 10.0).clamp(0.0, 1.0); // Same normalisation as node visuals
+    let pulse = (elapsed * PULSE_FREQUENCY).sin() * 0.5 + 0.5;
+    base * (1.0 + PULSE_AMPLITUDE * intensity * pulse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outline_width_scales_with_strength() {
+        let t = std::f32::consts::FRAC_PI_8; // Pulse at its peak
+        assert_eq!(outline_width(0.0, t, true), SELECTED_OUTLINE_WIDTH);
+        assert!(outline_width(10.0, t, true) > outline_width(1.0, t, true));
+        assert!(outline_width(10.0, t, false) <= HOVER_OUTLINE_WIDTH * (1.0 + PULSE_AMPLITUDE));
+    }
+}
@@ -0,0 +1,301 @@
+use bevy::{
+    prelude::*,
+    core_pipeline::core_3d::Transparent3d,
+    ecs::{
+        query::QueryItem,
+        system::{lifetimeless::*, SystemParamItem},
+    },
+    pbr::{MeshPipeline, MeshPipelineKey, RenderMeshInstances, SetMeshBindGroup, SetMeshViewBindGroup},
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        mesh::{allocator::MeshAllocator, MeshVertexBufferLayoutRef, RenderMesh, RenderMeshBufferInfo},
+        render_asset::RenderAssets,
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+            RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+        },
+        render_resource::*,
+        renderer::RenderDevice,
+        sync_world::MainEntity,
+        view::ExtractedView,
+        Render, RenderApp, RenderSet,
+    },
+};
+use crate::resources::uni_color::UniColor;
+
+const PARTICLE_SHADER_PATH: &str = "shaders/cpu_particles.wgsl";
+
+/// One CPU particle as uploaded to the GPU: centre and size, then linear colour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParticleInstance {
+    pub position: Vec3,
+    pub size: f32,
+    pub color: [f32; 4],
+}
+
+impl ParticleInstance {
+    /// Bytes per instance in the vertex buffer
+    pub const STRIDE: u64 = 8 * 4;
+
+    pub fn new(position: Vec3, size: f32, color: UniColor) -> Self {
+        let linear = color.as_linear_rgba();
+        Self {
+            position,
+            size,
+            color: [linear.red, linear.green, linear.blue, linear.alpha],
+        }
+    }
+
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        let [x, y, z] = self.position.to_array();
+        for value in [x, y, z, self.size].into_iter().chain(self.color) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+/// Every live CPU particle, drawn as instances of this entity's quad mesh
+#[derive(Component, Debug, Default, Clone)]
+pub struct ParticleInstances(pub Vec<ParticleInstance>);
+
+impl ExtractComponent for ParticleInstances {
+    type QueryData = &'static ParticleInstances;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(item: QueryItem<'_, Self::QueryData>) -> Option<Self> {
+        Some(item.clone())
+    }
+}
+
+/// Draws `ParticleInstances` as camera-facing billboards in one instanced call
+/// per view, in the transparent pass
+pub struct ParticleInstancingPlugin;
+
+impl Plugin for ParticleInstancingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ExtractComponentPlugin::<ParticleInstances>::default());
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.add_render_command::<Transparent3d, DrawParticleInstances>();
+        render_app.init_resource::<SpecializedMeshPipelines<ParticleInstancePipeline>>();
+        render_app.add_systems(Render, queue_particle_instances.in_set(RenderSet::QueueMeshes));
+        render_app.add_systems(Render, prepare_particle_instance_buffers.in_set(RenderSet::PrepareResources));
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<ParticleInstancePipeline>();
+        }
+    }
+}
+
+#[derive(Component)]
+struct ParticleInstanceBuffer {
+    buffer: Buffer,
+    length: u32,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn queue_particle_instances(
+    draw_functions: Res<DrawFunctions<Transparent3d>>,
+    pipeline: Res<ParticleInstancePipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<ParticleInstancePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    meshes: Res<RenderAssets<RenderMesh>>,
+    mesh_instances: Res<RenderMeshInstances>,
+    batches: Query<(Entity, &MainEntity, &ParticleInstances)>,
+    mut phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    views: Query<(Entity, &ExtractedView, &Msaa)>,
+) {
+    let draw_function = draw_functions.read().id::<DrawParticleInstances>();
+
+    for (view_entity, view, msaa) in views.iter() {
+        let Some(phase) = phases.get_mut(&view_entity) else {
+            continue;
+        };
+        let view_key = MeshPipelineKey::from_msaa_samples(msaa.samples())
+            | MeshPipelineKey::from_hdr(view.hdr)
+            | MeshPipelineKey::BLEND_ALPHA;
+        let rangefinder = view.rangefinder3d();
+
+        for (entity, main_entity, instances) in batches.iter() {
+            if instances.0.is_empty() {
+                continue;
+            }
+            let Some(mesh_instance) = mesh_instances.render_mesh_queue_data(*main_entity) else {
+                continue;
+            };
+            let Some(mesh) = meshes.get(mesh_instance.mesh_asset_id) else {
+                continue;
+            };
+            let key = view_key | MeshPipelineKey::from_primitive_topology(mesh.primitive_topology());
+            let Ok(pipeline_id) = pipelines.specialize(&pipeline_cache, &pipeline, key, &mesh.layout) else {
+                continue;
+            };
+
+            phase.add(Transparent3d {
+                entity: (entity, *main_entity),
+                pipeline: pipeline_id,
+                draw_function,
+                distance: rangefinder.distance_translation(&mesh_instance.translation),
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::NONE,
+            });
+        }
+    }
+}
+
+fn prepare_particle_instance_buffers(
+    mut commands: Commands,
+    batches: Query<(Entity, &ParticleInstances)>,
+    render_device: Res<RenderDevice>,
+    mut bytes: Local<Vec<u8>>,
+) {
+    for (entity, instances) in batches.iter() {
+        if instances.0.is_empty() {
+            commands.entity(entity).remove::<ParticleInstanceBuffer>();
+            continue;
+        }
+        bytes.clear();
+        for instance in instances.0.iter() {
+            instance.write_bytes(&mut bytes);
+        }
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("cpu_particle_instances"),
+            contents: &bytes,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        });
+        commands.entity(entity).insert(ParticleInstanceBuffer {
+            buffer,
+            length: instances.0.len() as u32,
+        });
+    }
+}
+
+#[derive(Resource)]
+struct ParticleInstancePipeline {
+    shader: Handle<Shader>,
+    mesh_pipeline: MeshPipeline,
+}
+
+impl FromWorld for ParticleInstancePipeline {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            shader: world.load_asset(PARTICLE_SHADER_PATH),
+            mesh_pipeline: world.resource::<MeshPipeline>().clone(),
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for ParticleInstancePipeline {
+    type Key = MeshPipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> std::result::Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.mesh_pipeline.specialize(key, layout)?;
+        descriptor.label = Some("cpu_particle_instance_pipeline".into());
+        descriptor.vertex.shader = self.shader.clone();
+        descriptor.vertex.buffers.push(VertexBufferLayout {
+            array_stride: ParticleInstance::STRIDE,
+            step_mode: VertexStepMode::Instance,
+            attributes: vec![
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 0,
+                    shader_location: 3,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VertexFormat::Float32x4.size(),
+                    shader_location: 4,
+                },
+            ],
+        });
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader = self.shader.clone();
+        }
+        // Billboards are seen from both sides as the camera orbits
+        descriptor.primitive.cull_mode = None;
+        Ok(descriptor)
+    }
+}
+
+type DrawParticleInstances = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMeshBindGroup<1>,
+    DrawMeshInstanced,
+);
+
+struct DrawMeshInstanced;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawMeshInstanced {
+    type Param = (SRes<RenderAssets<RenderMesh>>, SRes<RenderMeshInstances>, SRes<MeshAllocator>);
+    type ViewQuery = ();
+    type ItemQuery = Read<ParticleInstanceBuffer>;
+
+    fn render<'w>(
+        item: &P,
+        _view: (),
+        instance_buffer: Option<&'w ParticleInstanceBuffer>,
+        (meshes, mesh_instances, mesh_allocator): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let mesh_allocator = mesh_allocator.into_inner();
+        let Some(instance_buffer) = instance_buffer else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(mesh_instance) = mesh_instances.render_mesh_queue_data(item.main_entity()) else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(gpu_mesh) = meshes.into_inner().get(mesh_instance.mesh_asset_id) else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(vertex_slice) = mesh_allocator.mesh_vertex_slice(&mesh_instance.mesh_asset_id) else {
+            return RenderCommandResult::Skip;
+        };
+
+        pass.set_vertex_buffer(0, vertex_slice.buffer.slice(..));
+        pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
+
+        match &gpu_mesh.buffer_info {
+            RenderMeshBufferInfo::Indexed { index_format, count } => {
+                let Some(index_slice) = mesh_allocator.mesh_index_slice(&mesh_instance.mesh_asset_id) else {
+                    return RenderCommandResult::Skip;
+                };
+                pass.set_index_buffer(index_slice.buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(
+                    index_slice.range.start..(index_slice.range.start + count),
+                    vertex_slice.range.start as i32,
+                    0..instance_buffer.length,
+                );
+            }
+            RenderMeshBufferInfo::NonIndexed => {
+                pass.draw(vertex_slice.range, 0..instance_buffer.length);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_bytes_match_vertex_layout() {
+        let instance = ParticleInstance::new(Vec3::new(1.0, 2.0, 3.0), 0.5, UniColor::white());
+        let mut bytes = Vec::new();
+        instance.write_bytes(&mut bytes);
+        instance.write_bytes(&mut bytes);
+
+        assert_eq!(bytes.len() as u64, ParticleInstance::STRIDE * 2);
+        assert_eq!(f32::from_le_bytes(bytes[12..16].try_into().unwrap()), 0.5);
+        assert_eq!(f32::from_le_bytes(bytes[28..32].try_into().unwrap()), 1.0);
+    }
+}
@@ -5,84 +5,194 @@ use crate::{
         Node,
         MagneticField,
         Polarity,
-        particle_emitter::{ParticleEmitter, EmitterShape, InteractionEffect, Particle}
+        particle_emitter::{ParticleEmitter, EmitterShape, EmitterVelocity, InteractionEffect, Particle}
     },
     err::{Result, Error, ComponentError, ErrorManager},
-    resources::uni_color::{UniColor, presets::particle_gradient},
+    resources::{
+        theme::{Theme, ThemeSettings},
+        uni_color::UniColor,
+        EffectKind, EffectRegistry, ParticleBackend,
+    },
 };
 use bevy::math::Vec4;
 
-#[derive(Component)]
-#[derive(Resource)]
-pub struct MagneticEffects {
-    pub north: Handle<EffectAsset>,
-    pub south: Handle<EffectAsset>,
-    pub fallback: Handle<EffectAsset>,
+/// Particles emitted per second by effects built from a `ParticleEmitter`
+const EMITTER_SPAWN_RATE: f32 = 40.0;
+/// Lifetime in seconds of particles from emitter effects
+const EMITTER_PARTICLE_LIFETIME: f32 = 1.5;
+
+/// Init modifiers produced for emitter shapes and velocity modes. Hanabi's
+/// builder needs concrete modifier types, so this stands in for `Box<dyn Modifier>`.
+pub enum EmitterInitModifier {
+    PositionSphere(SetPositionSphereModifier),
+    PositionCircle(SetPositionCircleModifier),
+    PositionCone(SetPositionCone3dModifier),
+    VelocitySphere(SetVelocitySphereModifier),
+    VelocityTangent(SetVelocityTangentModifier),
+    Attribute(SetAttributeModifier),
+    /// Several attributes written in order, for definitions that set more than one
+    Attributes(Vec<SetAttributeModifier>),
+}
+
+impl EmitterInitModifier {
+    pub fn attach(self, effect: EffectAsset) -> EffectAsset {
+        match self {
+            EmitterInitModifier::PositionSphere(modifier) => effect.init(modifier),
+            EmitterInitModifier::PositionCircle(modifier) => effect.init(modifier),
+            EmitterInitModifier::PositionCone(modifier) => effect.init(modifier),
+            EmitterInitModifier::VelocitySphere(modifier) => effect.init(modifier),
+            EmitterInitModifier::VelocityTangent(modifier) => effect.init(modifier),
+            EmitterInitModifier::Attribute(modifier) => effect.init(modifier),
+            EmitterInitModifier::Attributes(modifiers) => modifiers
+                .into_iter()
+                .fold(effect, |effect, modifier| effect.init(modifier)),
+        }
+    }
+}
+
+/// Update modifiers available to data-driven effects, for the same reason as `EmitterInitModifier`
+pub enum EffectUpdateModifier {
+    LinearDrag(LinearDragModifier),
+    Accel(AccelModifier),
+    RadialAccel(RadialAccelModifier),
+    ConformToSphere(ConformToSphereModifier),
+}
+
+impl EffectUpdateModifier {
+    pub fn attach(self, effect: EffectAsset) -> EffectAsset {
+        match self {
+            EffectUpdateModifier::LinearDrag(modifier) => effect.update(modifier),
+            EffectUpdateModifier::Accel(modifier) => effect.update(modifier),
+            EffectUpdateModifier::RadialAccel(modifier) => effect.update(modifier),
+            EffectUpdateModifier::ConformToSphere(modifier) => effect.update(modifier),
+        }
+    }
 }
 
 /// Creates a position modifier based on the emitter shape
-fn create_position_modifier(shape: &EmitterShape, writer: &ExprWriter) -> Box<dyn Modifier> {
+pub fn create_position_modifier(shape: &EmitterShape, writer: &ExprWriter) -> EmitterInitModifier {
     match shape {
-        EmitterShape::Sphere { radius } => {
-            let init_pos = SetPositionSphereModifier {
-                center: writer.lit(Vec3::ZERO).expr(),
-                radius: writer.lit(*radius).expr(),
-                dimension: ShapeDimension::Volume,
-            };
-            Box::new(init_pos)
-        },
-        EmitterShape::Cone { height, radius } => {
-            let init_pos = SetPositionCone3dModifier {
-                base_radius: writer.lit(0.).expr(),
-                top_radius: writer.lit(*radius).expr(),
-                height: writer.lit(*height).expr(),
-                dimension: ShapeDimension::Volume,
-            };
-            Box::new(init_pos)
-        },
+        EmitterShape::Sphere { radius } => EmitterInitModifier::PositionSphere(SetPositionSphereModifier {
+            center: writer.lit(Vec3::ZERO).expr(),
+            radius: writer.lit(*radius).expr(),
+            dimension: ShapeDimension::Volume,
+        }),
+        // Base disc at the origin, apex `height` up +Y, matching `EmitterShape::sample`
+        EmitterShape::Cone { height, radius } => EmitterInitModifier::PositionCone(SetPositionCone3dModifier {
+            base_radius: writer.lit(*radius).expr(),
+            top_radius: writer.lit(0.).expr(),
+            height: writer.lit(*height).expr(),
+            dimension: ShapeDimension::Volume,
+        }),
         EmitterShape::Box { size } => {
-            let init_pos = SetPositionCircleModifier {
-                center: writer.lit(Vec3::ZERO).expr(),
-                radius: writer.lit(size.length() * 0.5).expr(),
-                axis: writer.lit(Vec3::Y).expr(),
-                dimension: ShapeDimension::Surface,
-            };
-            Box::new(init_pos)
-        },
+            let position = (writer.rand(VectorType::VEC3F) - writer.lit(Vec3::splat(0.5))) * writer.lit(*size);
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::POSITION, position.expr()))
+        }
+        EmitterShape::Torus { major_radius, minor_radius } => {
+            let tau = writer.lit(std::f32::consts::TAU);
+            let around = writer.rand(ScalarType::Float) * tau.clone();
+            let tube = writer.rand(ScalarType::Float) * tau;
+
+            let ring = writer.lit(Vec3::X) * around.clone().cos() + writer.lit(Vec3::Z) * around.sin();
+            let normal = ring.clone() * tube.clone().cos() + writer.lit(Vec3::Y) * tube.sin();
+            let position = ring * writer.lit(*major_radius) + normal * writer.lit(*minor_radius);
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::POSITION, position.expr()))
+        }
+        EmitterShape::Line { start, end } => {
+            let position = writer.lit(*start) + writer.lit(*end - *start) * writer.rand(ScalarType::Float);
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::POSITION, position.expr()))
+        }
+        EmitterShape::Ellipsoid { half_extents } => {
+            // Uniform direction: uniform height on the unit sphere, uniform angle around it
+            let height = writer.rand(ScalarType::Float) * writer.lit(2.0) - writer.lit(1.0);
+            let angle = writer.rand(ScalarType::Float) * writer.lit(std::f32::consts::TAU);
+            let ring = (writer.lit(1.0) - height.clone() * height.clone()).sqrt();
+            let direction = writer.lit(Vec3::X) * (ring.clone() * angle.clone().cos())
+                + writer.lit(Vec3::Y) * height
+                + writer.lit(Vec3::Z) * (ring * angle.sin());
+            let position = direction * writer.lit(*half_extents);
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::POSITION, position.expr()))
+        }
+        EmitterShape::MeshSurface { .. } => {
+            let approximation = shape
+                .gpu_approximation()
+                .unwrap_or(EmitterShape::Sphere { radius: 1.0 });
+            create_position_modifier(&approximation, writer)
+        }
     }
 }
 
-pub fn setup_particle_system(
-    mut commands: Commands,
-    mut effects: ResMut<Assets<EffectAsset>>,
-    _error_manager: ResMut<ErrorManager>,
-) {
-    let mut writer = ExprWriter::new();
-    
-    // Create gradient from our unified color system
-    let gradient = {
-        let mut gradient = Gradient::new();
-        for (pos, color) in particle_gradient() {
-            gradient.add_key(pos, color.as_vec4());
-        }
-        gradient
+/// Creates a velocity modifier for the emission mode; must be attached after the position modifier
+pub fn create_velocity_modifier(velocity: &EmitterVelocity, speed: f32, writer: &ExprWriter) -> EmitterInitModifier {
+    match velocity {
+        EmitterVelocity::Radial => EmitterInitModifier::VelocitySphere(SetVelocitySphereModifier {
+            center: writer.lit(Vec3::ZERO).expr(),
+            speed: writer.lit(speed).expr(),
+        }),
+        EmitterVelocity::Tangential { axis } => {
+            let position = writer.attr(Attribute::POSITION);
+            let velocity = writer.lit(*axis).cross(position).normalized() * writer.lit(speed);
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::VELOCITY, velocity.expr()))
+        }
+        EmitterVelocity::AlongField { axis } => {
+            let moment = writer.lit(axis.normalize_or_zero());
+            let r = writer.attr(Attribute::POSITION).normalized();
+            let field = r.clone() * (moment.clone().dot(r) * writer.lit(3.0)) - moment;
+            let velocity = field.normalized() * writer.lit(speed);
+            EmitterInitModifier::Attribute(SetAttributeModifier::new(Attribute::VELOCITY, velocity.expr()))
+        }
+    }
+}
+
+/// Build an effect whose spawn shape and velocity follow the emitter's configuration
+pub fn build_emitter_effect(emitter: &ParticleEmitter, gradient: Gradient<Vec4>) -> EffectAsset {
+    let writer = ExprWriter::new();
+
+    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.).expr());
+    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, writer.lit(EMITTER_PARTICLE_LIFETIME).expr());
+    let init_pos = create_position_modifier(&emitter.shape, &writer);
+    let init_vel = create_velocity_modifier(&emitter.velocity, emitter.speed, &writer);
+
+    let effect = EffectAsset::new(4096, Spawner::rate(EMITTER_SPAWN_RATE.into()), writer.finish())
+        .with_name("emitter")
+        .init(init_age)
+        .init(init_lifetime);
+
+    init_vel
+        .attach(init_pos.attach(effect))
+        .render(SizeOverLifetimeModifier {
+            gradient: Gradient::constant(Vec3::splat(0.05)),
+            screen_space_size: false,
+        })
+        .render(ColorOverLifetimeModifier { gradient })
+}
+
+/// Build the fallback effect used when an emitter configuration is invalid
+pub fn build_fallback_effect(gradient: Gradient<Vec4>) -> EffectAsset {
+    let writer = ExprWriter::new();
+
+    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, writer.lit(1.0).expr());
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(0.2).expr(),
+        dimension: ShapeDimension::Surface,
     };
 
-    let effect = EffectAsset::new(32768, Spawner::rate(30.0.into()), writer.clone().finish())
+    // 30 particles/s living 1s never need more than this
+    EffectAsset::new(64, Spawner::rate(30.0.into()), writer.finish())
         .with_name("fallback")
-        .init(SetAttributeModifier::new(Attribute::LIFETIME, writer.lit(1.0).expr()))
-        .init(SetPositionSphereModifier {
-            center: writer.lit(Vec3::ZERO).expr(),
-            radius: writer.lit(0.2).expr(),
-            dimension: ShapeDimension::Surface,
-        })
-        .render(ColorOverLifetimeModifier { gradient });
+        .init(init_lifetime)
+        .init(init_pos)
+        .render(ColorOverLifetimeModifier { gradient })
+}
 
-    commands.insert_resource(MagneticEffects {
-        north: effects.add(effect.clone()),
-        south: effects.add(effect.clone()),
-        fallback: effects.add(effect),
-    });
+pub fn setup_particle_system(
+    mut effects: ResMut<Assets<EffectAsset>>,
+    mut registry: ResMut<EffectRegistry>,
+) {
+    // Gradient comes from the default theme until a theme asset is applied
+    let effect = build_fallback_effect(Theme::default().particle_effect_gradient());
+    registry.insert(EffectKind::Fallback, effects.add(effect));
 }
 
 pub fn update_particles(
@@ -107,9 +217,55 @@ pub fn update_particles(
     }
 }
 
+/// Request for a short-lived emitter at a world position
+#[derive(Event, Debug, Clone)]
+pub struct SpawnEmitter {
+    pub position: Vec3,
+    pub emitter: ParticleEmitter,
+}
+
+/// Spawn requested emitters in the active theme's colours. On the CPU backend
+/// the emitter alone is spawned; the CPU particle systems simulate it.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_requested_emitters(
+    mut commands: Commands,
+    mut requests: EventReader<SpawnEmitter>,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    registry: Res<EffectRegistry>,
+    theme_settings: Res<ThemeSettings>,
+    themes: Res<Assets<Theme>>,
+    backend: Res<ParticleBackend>,
+    mut error_manager: ResMut<ErrorManager>,
+) {
+    let default_theme = Theme::default();
+    let theme = theme_settings
+        .active_handle()
+        .and_then(|handle| themes.get(handle))
+        .unwrap_or(&default_theme);
+
+    for request in requests.read() {
+        if *backend == ParticleBackend::Cpu {
+            commands.spawn((request.emitter.clone(), Transform::from_translation(request.position)));
+            continue;
+        }
+        spawn_particle_emitter(
+            &mut commands,
+            &mut effects,
+            &registry,
+            theme,
+            request.position,
+            request.emitter.clone(),
+            &mut error_manager,
+        );
+    }
+}
+
+/// Spawn an emitter with its own effect built from its shape and velocity mode
 pub fn spawn_particle_emitter(
     commands: &mut Commands,
-    magnetic_effects: &MagneticEffects,
+    effects: &mut Assets<EffectAsset>,
+    registry: &EffectRegistry,
+    theme: &Theme,
     position: Vec3,
     config: ParticleEmitter,
     error_manager: &mut ErrorManager,
@@ -121,7 +277,7 @@ pub fn spawn_particle_emitter(
         );
         commands.spawn((
             ParticleEffectBundle {
-                effect: bevy_hanabi::ParticleEffect::new(magnetic_effects.fallback.clone()),
+                effect: bevy_hanabi::ParticleEffect::new(registry.resolve(EffectKind::Fallback, effects)),
                 transform: Transform::from_translation(position),
                 ..default()
             },
@@ -130,10 +286,10 @@ pub fn spawn_particle_emitter(
         return;
     }
 
-    let effect_handle = match config.get_polarity() {
-        true => magnetic_effects.north.clone(),
-        false => magnetic_effects.south.clone(),
-    };
+    let effect_handle = effects.add(build_emitter_effect(
+        &config,
+        theme.polarity_gradient(config.get_polarity()),
+    ));
 
     commands.spawn((
         ParticleEffectBundle {
@@ -241,4 +397,40 @@ pub fn update_effect(
     transform.rotate(rotation);
 
     Ok(effect.lifetime > 0.0 && effect.lifetime.is_finite())
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_backend_spawns_emitters_without_effects() {
+        assert_eq!(" CPU ".parse::<ParticleBackend>().unwrap(), ParticleBackend::Cpu);
+        assert!("vulkan".parse::<ParticleBackend>().is_err());
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<Assets<EffectAsset>>();
+        app.init_resource::<Assets<Theme>>();
+        app.init_resource::<EffectRegistry>();
+        app.init_resource::<ThemeSettings>();
+        app.init_resource::<ErrorManager>();
+        app.insert_resource(ParticleBackend::Cpu);
+        app.add_event::<SpawnEmitter>();
+        app.add_systems(Update, spawn_requested_emitters);
+
+        app.world_mut().send_event(SpawnEmitter {
+            position: Vec3::X,
+            emitter: ParticleEmitter::default(),
+        });
+        app.update();
+
+        let mut emitters = app
+            .world_mut()
+            .query_filtered::<&Transform, (With<ParticleEmitter>, Without<bevy_hanabi::ParticleEffect>)>();
+        let spawned: Vec<_> = emitters.iter(app.world()).collect();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].translation, Vec3::X);
+        assert!(app.world().resource::<Assets<EffectAsset>>().is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+use bevy::{
+    prelude::*,
+    ecs::system::SystemParam,
+    picking::{
+        events::{Click, Pointer},
+        focus::PickingInteraction,
+        pointer::PointerButton,
+    },
+    window::PrimaryWindow,
+};
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    components::{Connection, Hovered, MagneticField, MainCamera, Node, Selected},
+    resources::{
+        input_bindings::HyvoAction,
+        selection::{Selection, SelectionChanged},
+        uni_color::UniColor,
+    },
+    systems::{field_visualization::FieldSlice, history::TimelineTrack},
+};
+
+/// Minimum drag distance in pixels before a press on empty space becomes a box select
+const BOX_SELECT_THRESHOLD: f32 = 4.0;
+
+/// Marks the UI rectangle drawn while box selecting
+#[derive(Component)]
+pub struct SelectionBox;
+
+/// In-progress box selection in window coordinates
+#[derive(Default)]
+pub struct BoxSelectDrag {
+    start: Option<Vec2>,
+    active: bool,
+}
+
+/// Read access to the selected nodes and their connections
+#[derive(SystemParam)]
+pub struct SelectedNodes<'w, 's> {
+    selection: Res<'w, Selection>,
+    nodes: Query<'w, 's, (&'static Node, Option<&'static MagneticField>, &'static Transform)>,
+    connections: Query<'w, 's, (Entity, &'static Connection)>,
+}
+
+/// A selected node with its field and the `Connection` entities touching it
+pub struct SelectedNode<'a> {
+    pub entity: Entity,
+    pub node: &'a Node,
+    pub field: MagneticField,
+    pub transform: &'a Transform,
+    pub connections: Vec<Entity>,
+}
+
+impl<'w, 's> SelectedNodes<'w, 's> {
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<SelectedNode<'_>> {
+        let (node, field, transform) = self.nodes.get(entity).ok()?;
+        let connections = self
+            .connections
+            .iter()
+            .filter(|(_, connection)| connection.start == entity || connection.end == entity)
+            .map(|(connection_entity, _)| connection_entity)
+            .collect();
+
+        Some(SelectedNode {
+            entity,
+            node,
+            // Prefer the live component; fall back to the node's own copy
+            field: field.copied().unwrap_or(node.magnetic_field),
+            transform,
+            connections,
+        })
+    }
+
+    pub fn primary(&self) -> Option<SelectedNode<'_>> {
+        self.selection.primary().and_then(|entity| self.get(entity))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = SelectedNode<'_>> + '_ {
+        self.selection.iter().filter_map(|entity| self.get(entity))
+    }
+}
+
+pub fn setup_selection_box(mut commands: Commands) {
+    let color = UniColor::srgb(0.4, 0.7, 1.0);
+
+    commands.spawn((
+        bevy::ui::Node {
+            position_type: PositionType::Absolute,
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BorderColor(color.as_bevy_color()),
+        BackgroundColor(color.with_alpha(0.1).as_bevy_color()),
+        Visibility::Hidden,
+        SelectionBox,
+    ));
+}
+
+/// Click selects, shift-click toggles, and dragging from empty space box selects
+#[allow(clippy::too_many_arguments)]
+pub fn update_selection(
+    mut click_events: EventReader<Pointer<Click>>,
+    actions: Res<ActionState<HyvoAction>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    nodes: Query<(Entity, &GlobalTransform, Has<Hovered>), With<Node>>,
+    mut boxes: Query<(&mut bevy::ui::Node, &mut Visibility), With<SelectionBox>>,
+    slices: Query<&PickingInteraction, With<FieldSlice>>,
+    timelines: Query<&Interaction, With<TimelineTrack>>,
+    mut selection: ResMut<Selection>,
+    mut drag: Local<BoxSelectDrag>,
+) {
+    let additive = actions.pressed(&HyvoAction::ExtendSelection);
+    let cursor = windows.get_single().ok().and_then(|window| window.cursor_position());
+
+    // Presses on the timeline scrub it and never reach the scene
+    let over_timeline = timelines.iter().any(|interaction| *interaction != Interaction::None);
+    let over_node = nodes.iter().any(|(_, _, hovered)| hovered);
+    let over_slice = slices.iter().any(|interaction| *interaction != PickingInteraction::None);
+
+    // Box selection only starts when the press is not over a node, the field slice or the timeline
+    if actions.just_pressed(&HyvoAction::Select) {
+        drag.start = if over_node || over_slice || over_timeline { None } else { cursor };
+        drag.active = false;
+    }
+
+    if let (Some(start), Some(cursor)) = (drag.start, cursor) {
+        if actions.pressed(&HyvoAction::Select) && start.distance(cursor) > BOX_SELECT_THRESHOLD {
+            drag.active = true;
+        }
+
+        if let Ok((mut ui_node, mut visibility)) = boxes.get_single_mut() {
+            if drag.active {
+                let min = start.min(cursor);
+                let size = (start - cursor).abs();
+                ui_node.left = Val::Px(min.x);
+                ui_node.top = Val::Px(min.y);
+                ui_node.width = Val::Px(size.x);
+                ui_node.height = Val::Px(size.y);
+                *visibility = Visibility::Visible;
+            }
+        }
+
+        if actions.just_released(&HyvoAction::Select) && drag.active {
+            if let Ok((camera, camera_transform)) = cameras.get_single() {
+                let rect = Rect::from_corners(start, cursor);
+                let inside: Vec<Entity> = nodes
+                    .iter()
+                    .filter(|(_, transform, _)| {
+                        camera
+                            .world_to_viewport(camera_transform, transform.translation())
+                            .is_ok_and(|point| rect.contains(point))
+                    })
+                    .map(|(entity, _, _)| entity)
+                    .collect();
+
+                if additive {
+                    selection.extend(inside);
+                } else {
+                    selection.set(inside);
+                }
+            }
+        }
+    }
+
+    // Picking has no background target, so a plain click on empty space is
+    // detected here rather than from a `Pointer<Click>`
+    if actions.just_released(&HyvoAction::Select) {
+        if !drag.active && !additive && !over_node && !over_slice && !over_timeline {
+            selection.clear();
+        }
+        drag.start = None;
+        drag.active = false;
+        if let Ok((_, mut visibility)) = boxes.get_single_mut() {
+            *visibility = Visibility::Hidden;
+        }
+    }
+
+    for event in click_events.read() {
+        if event.button != PointerButton::Primary || !nodes.contains(event.target) {
+            continue;
+        }
+        if additive {
+            selection.toggle(event.target);
+        } else {
+            selection.select(event.target);
+        }
+    }
+}
+
+/// Keeps `Selected` markers in sync with the `Selection` resource and emits change events
+pub fn sync_selection(
+    mut commands: Commands,
+    mut selection: ResMut<Selection>,
+    nodes: Query<(), With<Node>>,
+    selected: Query<Entity, With<Selected>>,
+    mut changed_events: EventWriter<SelectionChanged>,
+) {
+    // Drop entities that were despawned while selected
+    if selection.iter().any(|entity| !nodes.contains(entity)) {
+        selection.retain(|entity| nodes.contains(*entity));
+    }
+
+    let removed: Vec<Entity> = selected
+        .iter()
+        .filter(|entity| !selection.contains(*entity))
+        .collect();
+    let added: Vec<Entity> = selection
+        .iter()
+        .filter(|entity| !selected.contains(*entity))
+        .collect();
+
+    if added.is_empty() && removed.is_empty() {
+        return;
+    }
+
+    for entity in removed.iter() {
+        commands.entity(*entity).remove::<Selected>();
+    }
+    for entity in added.iter() {
+        commands.entity(*entity).try_insert(Selected);
+    }
+
+    changed_events.send(SelectionChanged { added, removed });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_on_empty_space_deselects() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_event::<Pointer<Click>>();
+        app.init_resource::<Selection>();
+        app.init_resource::<ActionState<HyvoAction>>();
+        app.add_systems(Update, update_selection);
+
+        let node = app.world_mut().spawn((Node::default(), GlobalTransform::default())).id();
+        app.world_mut().resource_mut::<Selection>().select(node);
+
+        app.world_mut().resource_mut::<ActionState<HyvoAction>>().press(&HyvoAction::Select);
+        app.update();
+        assert!(!app.world().resource::<Selection>().is_empty());
+
+        app.world_mut().resource_mut::<ActionState<HyvoAction>>().release(&HyvoAction::Select);
+        app.update();
+        assert!(app.world().resource::<Selection>().is_empty());
+    }
+}
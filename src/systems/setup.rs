@@ -10,7 +10,8 @@ use bevy::{
 };
 use crate::{
     components::{Mesh3d, MeshMaterial3d, MainCamera, WindowBorder},
-    resources::{MaterialHandles, uni_color::UniColor},
+    resources::{CameraState, MaterialColors, MaterialHandles, uni_color::UniColor},
+    systems::camera::OrbitCamera,
     err::{Error, ErrorManager, ComponentError, ResourceError},
 };
 
@@ -20,66 +21,37 @@ pub struct Shape;
 
 pub fn setup_camera(
     mut commands: Commands,
+    camera_state: Res<CameraState>,
     mut error_manager: ResMut<ErrorManager>,
 ) {
-    let camera_position = Vec3::new(-2.0, 2.5, 5.0);
-    let look_at = Vec3::ZERO;
-
-    if camera_position.distance(look_at) < 0.1 {
+    let (min_radius, _) = CameraState::RADIUS_LIMITS;
+    if camera_state.target_radius < min_radius {
         error_manager.report_error(Error::Component(ComponentError::ValidationFailed(
             "Camera position too close to look_at point".into()
         )));
         return;
     }
 
+    let orbit = OrbitCamera::from_state(&camera_state);
     commands.spawn((
         Camera3dBundle {
-            transform: Transform::from_translation(camera_position)
-                .looking_at(look_at, Vec3::Y),
+            transform: orbit.transform(),
             ..default()
         },
+        orbit,
         MainCamera,
+        // UI stays on the perspective view when the window is split
+        IsDefaultUiCamera,
     ));
 }
 
 pub fn setup_materials(
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut material_handles: ResMut<MaterialHandles>,
-    _error_manager: ResMut<ErrorManager>,
+    colors: Res<MaterialColors>,
 ) {
-    // Create default material
-    let base_color = UniColor::srgb(0.98, 0.96, 0.93);
-    let emissive = UniColor::srgb(1.0, 1.0, 0.0);
-    
-    let material = StandardMaterial {
-        base_color: base_color.as_bevy_color(),
-        emissive: emissive.as_linear_rgba(),
-        ..default()
-    };
-
-    material_handles.node_material = materials.add(material);
-
-    // Create connection material
-    let connection_color = UniColor::srgb(0.0, 0.0, 1.0).with_alpha(0.8);
-    let connection_material = StandardMaterial {
-        base_color: connection_color.as_bevy_color(),
-        alpha_mode: AlphaMode::Blend,
-        ..default()
-    };
-
-    material_handles.connection_material = materials.add(connection_material);
-
-    // Create highlight material
-    let highlight_color = UniColor::srgb(1.0, 0.84, 0.0);
-    let highlight_emissive = UniColor::srgb(1.0, 1.0, 0.0);
-    
-    let highlight_material = StandardMaterial {
-        base_color: highlight_color.as_bevy_color(),
-        emissive: highlight_emissive.as_linear_rgba(),
-        ..default()
-    };
-
-    material_handles.highlight_material = materials.add(highlight_material);
+    // Colours come from the active theme; see `systems::theme::apply_theme`
+    material_handles.apply_colors(&colors, &mut materials);
 }
 
 pub fn setup_scene(
@@ -134,7 +106,7 @@ pub fn setup_window_border(
 }
 
 pub fn animate_window_border(
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     mut query: Query<&mut WindowBorder>,
 ) {
     let elapsed = time.elapsed_secs_f64() as f32;
@@ -155,6 +127,7 @@ mod tests {
     fn test_camera_setup() {
         let mut app = App::new();
         app.init_resource::<ErrorManager>();
+        app.init_resource::<CameraState>();
         
         app.add_systems(Startup, setup_camera);
         app.update();
@@ -167,6 +140,7 @@ mod tests {
         let mut app = App::new();
         app.init_resource::<ErrorManager>();
         app.init_resource::<MaterialHandles>();
+        app.init_resource::<MaterialColors>();
         app.init_resource::<Assets<StandardMaterial>>();
         
         app.add_systems(Startup, setup_materials);
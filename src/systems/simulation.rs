@@ -0,0 +1,63 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use crate::resources::{input_bindings::HyvoAction, AnimationState, RegenerateHelix};
+
+/// Build the first helix once the app starts
+pub fn request_initial_helix(mut requests: EventWriter<RegenerateHelix>) {
+    requests.send(RegenerateHelix);
+}
+
+/// Pause, speed, single step and reset
+pub fn control_simulation(
+    actions: Res<ActionState<HyvoAction>>,
+    mut state: ResMut<AnimationState>,
+    mut requests: EventWriter<RegenerateHelix>,
+) {
+    if actions.just_pressed(&HyvoAction::Pause) {
+        state.toggle_pause();
+        info!("Simulation {}", if state.paused { "paused" } else { "resumed" });
+    }
+    if actions.just_pressed(&HyvoAction::SpeedUp) {
+        state.scale_speed(AnimationState::SPEED_STEP);
+        info!("Simulation speed {}x", state.speed);
+    }
+    if actions.just_pressed(&HyvoAction::SpeedDown) {
+        state.scale_speed(AnimationState::SPEED_STEP.recip());
+        info!("Simulation speed {}x", state.speed);
+    }
+    if actions.just_pressed(&HyvoAction::Step) {
+        state.request_step();
+    }
+    if actions.just_pressed(&HyvoAction::Reset) {
+        requests.send(RegenerateHelix);
+    }
+}
+
+/// Apply `AnimationState` to virtual time. Runs in `First` after the clocks
+/// advance, so pause and speed changes take effect from the next frame; a step
+/// advances this frame's paused clock by exactly one tick instead.
+pub fn sync_simulation_clock(
+    mut state: ResMut<AnimationState>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut time: ResMut<Time>,
+) {
+    if state.paused && !virtual_time.is_paused() {
+        virtual_time.pause();
+    } else if !state.paused && virtual_time.is_paused() {
+        virtual_time.unpause();
+    }
+    // Virtual time panics on a negative speed, and the inspector can set any value
+    if !state.speed_in_limits() {
+        state.clamp_speed();
+    }
+    if virtual_time.relative_speed() != state.speed {
+        virtual_time.set_relative_speed(state.speed);
+    }
+
+    if std::mem::take(&mut state.step_requested) && state.paused {
+        virtual_time.advance_by(Duration::from_secs_f32(AnimationState::STEP));
+        // `Update` reads the generic clock, which was copied before the step
+        *time = virtual_time.as_generic();
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    resources::{
+        effect_definition::EffectLibrary,
+        input_bindings::HyvoAction,
+        theme::{Theme, ThemeSettings, THEME_PATHS},
+        MaterialColors, MaterialHandles,
+    },
+    err::{Error, ErrorManager, ResourceError},
+};
+
+pub fn load_themes(
+    asset_server: Res<AssetServer>,
+    mut settings: ResMut<ThemeSettings>,
+) {
+    settings.themes = THEME_PATHS
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+    settings.active = 0;
+    settings.dirty = true;
+}
+
+pub fn cycle_theme(
+    actions: Res<ActionState<HyvoAction>>,
+    mut settings: ResMut<ThemeSettings>,
+) {
+    if actions.just_pressed(&HyvoAction::CycleTheme) {
+        settings.cycle();
+    }
+}
+
+/// Applies the active theme when it is selected, first loaded or hot reloaded
+#[allow(clippy::too_many_arguments)]
+pub fn apply_theme(
+    mut commands: Commands,
+    mut theme_events: EventReader<AssetEvent<Theme>>,
+    mut settings: ResMut<ThemeSettings>,
+    themes: Res<Assets<Theme>>,
+    mut colors: ResMut<MaterialColors>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut material_handles: ResMut<MaterialHandles>,
+    mut effect_library: ResMut<EffectLibrary>,
+    error_manager: Res<ErrorManager>,
+) {
+    let Some(active) = settings.active_handle().cloned() else {
+        return;
+    };
+
+    for event in theme_events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }
+                if *id == active.id() =>
+            {
+                settings.dirty = true;
+            }
+            _ => {}
+        }
+    }
+
+    if !settings.dirty {
+        return;
+    }
+
+    // Keep waiting until the asset finishes loading
+    let Some(theme) = themes.get(&active) else {
+        return;
+    };
+    settings.dirty = false;
+
+    if let Err(e) = theme.validate() {
+        error_manager.report_with_recovery(e, "Keeping the previous theme");
+        return;
+    }
+
+    *colors = theme.material_colors();
+    material_handles.apply_colors(&colors, &mut materials);
+    commands.insert_resource(ClearColor(theme.background.as_bevy_color()));
+
+    // Effects with theme gradients are rebuilt from their definitions
+    effect_library.dirty = true;
+
+    info!("Applied theme '{}'", theme.name);
+}
+
+/// Reports themes that failed to load so a typo in a hot-reloaded file is visible
+pub fn report_theme_errors(
+    asset_server: Res<AssetServer>,
+    settings: Res<ThemeSettings>,
+    error_manager: Res<ErrorManager>,
+    mut reported: Local<Vec<AssetId<Theme>>>,
+) {
+    for handle in settings.themes.iter() {
+        let failed = matches!(
+            asset_server.get_load_state(handle.id()),
+            Some(bevy::asset::LoadState::Failed(_))
+        );
+        if failed && !reported.contains(&handle.id()) {
+            reported.push(handle.id());
+            error_manager.report_error(Error::Resource(ResourceError::LoadFailed(format!(
+                "Theme {:?} failed to load",
+                handle.path()
+            ))));
+        } else if !failed {
+            reported.retain(|id| *id != handle.id());
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    components::{MagneticField, MotionTrail, Node},
+    resources::{input_bindings::HyvoAction, Colormap, MaterialColors, TrailColorMode, TrailSettings},
+    err::ErrorManager,
+};
+
+/// Toggles trails, or switches between speed and polarity colouring
+pub fn toggle_motion_trails(
+    actions: Res<ActionState<HyvoAction>>,
+    mut settings: ResMut<TrailSettings>,
+) {
+    if actions.just_pressed(&HyvoAction::CycleTrailColors) {
+        settings.color_mode = match settings.color_mode {
+            TrailColorMode::Speed => TrailColorMode::Polarity,
+            TrailColorMode::Polarity => TrailColorMode::Speed,
+        };
+    } else if actions.just_pressed(&HyvoAction::ToggleTrails) {
+        settings.enabled = !settings.enabled;
+    }
+}
+
+/// Give every node a trail and keep trail capacity in step with the settings
+pub fn attach_motion_trails(
+    mut commands: Commands,
+    settings: Res<TrailSettings>,
+    new_nodes: Query<Entity, (With<Node>, Without<MotionTrail>)>,
+    mut trails: Query<&mut MotionTrail>,
+) {
+    for entity in new_nodes.iter() {
+        commands.entity(entity).try_insert(MotionTrail::new(settings.length));
+    }
+
+    if settings.is_changed() {
+        for mut trail in trails.iter_mut() {
+            trail.resize(settings.length);
+        }
+    }
+}
+
+/// Sample node positions at the configured rate. Trails are cleared while
+/// disabled so re-enabling does not draw a jump across the gap.
+pub fn record_motion_trails(
+    time: Res<Time>,
+    settings: Res<TrailSettings>,
+    mut trails: Query<(&GlobalTransform, &mut MotionTrail)>,
+    error_manager: Res<ErrorManager>,
+) {
+    if !settings.enabled {
+        if settings.is_changed() {
+            for (_, mut trail) in trails.iter_mut() {
+                trail.clear();
+            }
+        }
+        return;
+    }
+    if settings.is_changed() {
+        if let Err(e) = settings.validate() {
+            error_manager.report_error(e);
+            return;
+        }
+    }
+
+    let dt = time.delta_secs();
+    for (transform, mut trail) in trails.iter_mut() {
+        trail.since_sample += dt;
+        if trail.since_sample < settings.sample_interval {
+            continue;
+        }
+
+        let position = transform.translation();
+        let moved = trail
+            .latest()
+            .is_none_or(|last| last.position.distance(position) >= settings.min_distance);
+        if moved {
+            let elapsed = trail.since_sample;
+            trail.push(position, elapsed);
+        }
+        trail.since_sample = 0.0;
+    }
+}
+
+/// Draw each trail as a polyline fading from transparent at its tail to
+/// opaque at the node
+pub fn draw_motion_trails(
+    settings: Res<TrailSettings>,
+    colors: Res<MaterialColors>,
+    trails: Query<(&MotionTrail, &GlobalTransform, Option<&MagneticField>)>,
+    mut gizmos: Gizmos,
+) {
+    if !settings.enabled {
+        return;
+    }
+    let colormap = Colormap::preset(settings.colormap);
+
+    for (trail, transform, field) in trails.iter() {
+        if trail.len() < 2 {
+            continue;
+        }
+        let count = trail.len() as f32;
+        let polarity = field.map(|field| colors.polarity(field.polarity));
+        let color_for = |speed: f32| match (settings.color_mode, polarity) {
+            (TrailColorMode::Polarity, Some(color)) => color,
+            _ => colormap.sample_range(speed, 0.0, settings.max_speed),
+        };
+        let head_speed = trail.latest().map_or(0.0, |last| last.speed);
+
+        let points = trail
+            .iter()
+            .enumerate()
+            .map(|(index, sample)| {
+                let age = (index + 1) as f32 / count;
+                (sample.position, color_for(sample.speed).with_alpha(age).as_bevy_color())
+            })
+            // Close the gap between the newest sample and the node itself
+            .chain(std::iter::once((transform.translation(), color_for(head_speed).as_bevy_color())));
+
+        gizmos.linestrip_gradient(points);
+    }
+}
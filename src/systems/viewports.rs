@@ -0,0 +1,149 @@
+use bevy::{
+    prelude::*,
+    input::mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    render::camera::{ScalingMode, Viewport},
+    window::PrimaryWindow,
+};
+use leafwing_input_manager::prelude::ActionState;
+use crate::{
+    components::MainCamera,
+    resources::{
+        input_bindings::HyvoAction,
+        viewports::{OrthoAxis, ViewportLayout},
+        CameraState,
+    },
+};
+
+/// Distance of orthographic cameras from the focus; only affects clipping
+const ORTHO_DISTANCE: f32 = 100.0;
+/// Visible height of an orthographic view when the split opens
+const DEFAULT_ORTHO_HEIGHT: f32 = 16.0;
+/// Zoom factor per scroll line
+const ORTHO_ZOOM_STEP: f32 = 1.1;
+/// Pixels treated as one line for touchpad scrolling
+const PIXELS_PER_LINE: f32 = 20.0;
+/// Limits on the visible height of an orthographic view
+const ORTHO_HEIGHT_LIMITS: (f32, f32) = (0.5, 500.0);
+
+/// An orthographic camera in the split layout with its own zoom
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OrthoView {
+    pub axis: OrthoAxis,
+    /// Visible height in world units
+    pub height: f32,
+}
+
+pub fn setup_ortho_views(mut commands: Commands) {
+    for axis in OrthoAxis::ALL {
+        commands.spawn((
+            Camera3d::default(),
+            Camera {
+                order: axis.quadrant() as isize,
+                is_active: false,
+                ..default()
+            },
+            Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical { viewport_height: 1.0 },
+                scale: DEFAULT_ORTHO_HEIGHT,
+                far: ORTHO_DISTANCE * 2.0,
+                ..OrthographicProjection::default_3d()
+            }),
+            Transform::default(),
+            OrthoView {
+                axis,
+                height: DEFAULT_ORTHO_HEIGHT,
+            },
+        ));
+    }
+}
+
+/// Toggles the four-way split
+pub fn toggle_viewport_split(
+    actions: Res<ActionState<HyvoAction>>,
+    mut layout: ResMut<ViewportLayout>,
+) {
+    if actions.just_pressed(&HyvoAction::ToggleSplitView) {
+        layout.split = !layout.split;
+    }
+}
+
+/// Scrolling over an orthographic view zooms that view only
+pub fn zoom_ortho_views(
+    scroll: Res<AccumulatedMouseScroll>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut views: Query<(&Camera, &mut OrthoView)>,
+) {
+    if scroll.delta.y == 0.0 {
+        return;
+    }
+    let Some(cursor) = windows.get_single().ok().and_then(|window| window.physical_cursor_position()) else {
+        return;
+    };
+    let lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_LINE,
+    };
+
+    for (camera, mut view) in views.iter_mut() {
+        let Some(viewport) = camera.viewport.as_ref().filter(|_| camera.is_active) else {
+            continue;
+        };
+        let min = viewport.physical_position.as_vec2();
+        let max = min + viewport.physical_size.as_vec2();
+        if Rect::from_corners(min, max).contains(cursor) {
+            let (min_height, max_height) = ORTHO_HEIGHT_LIMITS;
+            view.height = (view.height * ORTHO_ZOOM_STEP.powf(-lines)).clamp(min_height, max_height);
+        }
+    }
+}
+
+/// Lay cameras out for the current window size and keep orthographic views
+/// centred on the orbit focus
+pub fn update_viewports(
+    layout: Res<ViewportLayout>,
+    camera_state: Res<CameraState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut main_cameras: Query<&mut Camera, (With<MainCamera>, Without<OrthoView>)>,
+    mut views: Query<(&mut Camera, &mut Transform, &mut Projection, &OrthoView), Without<MainCamera>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let size = window.physical_size();
+    let viewport_for = |quadrant: u32| {
+        let (physical_position, physical_size) = layout.quadrant(size, quadrant);
+        Viewport {
+            physical_position,
+            physical_size,
+            ..default()
+        }
+    };
+
+    for mut camera in main_cameras.iter_mut() {
+        let viewport = layout.split.then(|| viewport_for(0));
+        if camera.viewport != viewport {
+            camera.viewport = viewport;
+        }
+    }
+
+    for (mut camera, mut transform, mut projection, view) in views.iter_mut() {
+        if camera.is_active != layout.split {
+            camera.is_active = layout.split;
+        }
+        if !layout.split {
+            continue;
+        }
+
+        let viewport = Some(viewport_for(view.axis.quadrant()));
+        if camera.viewport != viewport {
+            camera.viewport = viewport;
+        }
+
+        let focus = camera_state.target_focus;
+        *transform = Transform::from_translation(focus - view.axis.forward() * ORTHO_DISTANCE)
+            .looking_to(view.axis.forward(), view.axis.up());
+        if let Projection::Orthographic(orthographic) = projection.as_mut() {
+            orthographic.scale = view.height;
+        }
+    }
+}